@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+use toml::Value;
+use crate::verify::parse_day_tables;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+        Some(Self { min: sorted[0], median, max: sorted[n - 1] })
+    }
+}
+
+// Median timings from an earlier run, stored in nanoseconds as:
+//
+//   [day15]
+//   part1 = 412345678
+//   part2 = 598765432
+#[derive(Default)]
+pub struct Baseline(BTreeMap<u32, [Option<Duration>; 2]>);
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+        text.parse::<Self>().map_err(|e| format!("{path}: {e}"))
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("cannot write {path}: {e}"))
+    }
+    pub fn get(&self, day: u32, part: u32) -> Option<Duration> {
+        self.0.get(&day)
+            .and_then(|parts| parts.get(part as usize - 1))
+            .and_then(|d| *d)
+    }
+    pub fn set(&mut self, day: u32, part: u32, median: Duration) {
+        self.0.entry(day).or_default()[part as usize - 1] = Some(median);
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_day_tables(s, |key, v| match v {
+            Value::Integer(n) if *n >= 0 => Ok(Duration::from_nanos(*n as u64)),
+            _ => Err(format!("{key}: expected nanoseconds")),
+        }).map(Self)
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (day, parts) in &self.0 {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            writeln!(f, "[day{day:02}]")?;
+            for (idx, d) in parts.iter().enumerate() {
+                if let Some(d) = d {
+                    writeln!(f, "part{} = {}", idx + 1, d.as_nanos())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    New,
+    Ok(f64),
+    Regressed(f64),
+}
+
+// Ratio of the current median to the baseline; anything slower than
+// `threshold` (e.g. 0.10 for 10%) counts as a regression.
+pub fn compare(baseline: Option<Duration>, current: Duration, threshold: f64) -> Comparison {
+    match baseline {
+        None => Comparison::New,
+        Some(b) => {
+            let ratio = current.as_secs_f64() / b.as_secs_f64().max(1e-9);
+            if ratio > 1.0 + threshold {
+                Comparison::Regressed(ratio)
+            } else {
+                Comparison::Ok(ratio)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_test() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(&[ms(5), ms(1), ms(3), ms(9)]).unwrap();
+        assert_eq!(stats, Stats { min: ms(1), median: ms(4), max: ms(9) });
+        assert_eq!(Stats::from_samples(&[ms(2), ms(7), ms(3)]).unwrap().median, ms(3));
+        assert!(Stats::from_samples(&[]).is_none());
+
+        let mut baseline = Baseline::default();
+        baseline.set(15, 1, ms(400));
+        baseline.set(3, 2, Duration::from_nanos(1500));
+        let text = baseline.to_string();
+        assert_eq!(text, "[day03]\npart2 = 1500\n\n[day15]\npart1 = 400000000\n");
+        let reloaded = text.parse::<Baseline>().unwrap();
+        assert_eq!(reloaded.get(15, 1), Some(ms(400)));
+        assert_eq!(reloaded.get(15, 2), None);
+
+        assert_eq!(compare(None, ms(1), 0.1), Comparison::New);
+        assert!(matches!(compare(Some(ms(100)), ms(105), 0.1), Comparison::Ok(_)));
+        assert!(matches!(compare(Some(ms(100)), ms(120), 0.1), Comparison::Regressed(r) if r > 1.19));
+        assert!("[day15]\npart1 = -3\n".parse::<Baseline>().is_err());
+        assert!("[day00]\npart1 = 3\n".parse::<Baseline>().is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use std::vec::Vec;
+use advent2017::days::LAST_DAY;
+use advent2017::vm::{assemble, optimize, Arithmetic, Assembly, Dialect, Topology, VM};
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+pub struct Args {
+    pub days: Option<Vec<u32>>,
+    pub parts: Vec<u32>,
+    pub input: Option<String>,
+    pub format: Format,
+    pub answers: String,
+    pub runs: usize,
+    pub save: Option<String>,
+    pub compare: Option<String>,
+    pub threshold: f64,
+    pub trace: usize,
+    pub programs: usize,
+    pub topology: Topology,
+    pub threads: bool,
+    pub capacity: usize,
+    pub limit: Option<usize>,
+    pub optimize: bool,
+    pub regs: Vec<(String, i64)>,
+    pub output: Option<String>,
+    pub arith: Arithmetic,
+    pub at: Option<usize>,
+}
+
+fn parse_day(d: &str) -> Result<Vec<u32>, String> {
+    match d {
+        "all" => Ok((1..=LAST_DAY).collect()),
+        _ => match d.parse::<u32>() {
+            Ok(n) if (1..=LAST_DAY).contains(&n) => Ok(vec![n]),
+            _ => Err(format!("invalid day: {d}")),
+        },
+    }
+}
+
+// `allowed` lists the options the subcommand accepts.
+pub fn parse_args(args: &[String], allowed: &[&str]) -> Result<Args, String> {
+    let mut parsed = Args {
+        days: None,
+        parts: vec![1, 2],
+        input: None,
+        format: Format::Text,
+        answers: "answers.toml".into(),
+        runs: 10,
+        save: None,
+        compare: None,
+        threshold: 0.10,
+        trace: 20,
+        programs: 2,
+        topology: Topology::Ring,
+        threads: false,
+        capacity: 1024,
+        limit: None,
+        optimize: false,
+        regs: Vec::new(),
+        output: None,
+        arith: Arithmetic::default(),
+        at: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg.starts_with("--") && !allowed.contains(&arg.as_str()) {
+            return Err(format!("unexpected option: {arg}"));
+        }
+        let mut value = || iter.next().ok_or(format!("{arg} requires a value"));
+        match arg.as_str() {
+            "--part" => {
+                parsed.parts = match value()?.as_str() {
+                    "1" => vec![1],
+                    "2" => vec![2],
+                    _ => return Err("--part must be 1 or 2".into()),
+                };
+            },
+            "--input" => {
+                parsed.input = Some(value()?.clone());
+            },
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err("--format must be text or json".into()),
+                };
+            },
+            "--answers" => {
+                parsed.answers = value()?.clone();
+            },
+            "--runs" => {
+                parsed.runs = match value()?.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err("--runs must be a positive integer".into()),
+                };
+            },
+            "--save" => {
+                parsed.save = Some(value()?.clone());
+            },
+            "--compare" => {
+                parsed.compare = Some(value()?.clone());
+            },
+            "--threshold" => {
+                parsed.threshold = match value()?.parse::<f64>() {
+                    Ok(pct) if pct >= 0.0 => pct / 100.0,
+                    _ => return Err("--threshold must be a non-negative percentage".into()),
+                };
+            },
+            "--trace" => {
+                parsed.trace = value()?.parse::<usize>()
+                    .map_err(|_| "--trace must be a non-negative integer".to_string())?;
+            },
+            "--programs" => {
+                parsed.programs = match value()?.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err("--programs must be a positive integer".into()),
+                };
+            },
+            "--topology" => {
+                parsed.topology = value()?.parse::<Topology>()?;
+            },
+            "--threads" => {
+                parsed.threads = true;
+            },
+            "--capacity" => {
+                parsed.capacity = match value()?.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err("--capacity must be a positive integer".into()),
+                };
+            },
+            "--limit" => {
+                parsed.limit = Some(value()?.parse::<usize>()
+                    .map_err(|_| "--limit must be a non-negative integer".to_string())?);
+            },
+            "--optimize" => {
+                parsed.optimize = true;
+            },
+            "--reg" => {
+                let v = value()?;
+                parsed.regs.push(match v.split_once('=').map(|(r, n)| (r, n.parse::<i64>())) {
+                    Some((r, Ok(n))) if !r.is_empty() => (r.to_string(), n),
+                    _ => return Err(format!("--reg expects REG=VALUE, got {v}")),
+                });
+            },
+            "--arith" => {
+                parsed.arith = value()?.parse::<Arithmetic>()?;
+            },
+            "--at" => {
+                parsed.at = Some(value()?.parse::<usize>()
+                    .map_err(|_| "--at must be a pc".to_string())?);
+            },
+            "--output" => {
+                parsed.output = Some(value()?.clone());
+            },
+            _ if parsed.days.is_none() => {
+                parsed.days = Some(parse_day(arg)?);
+            },
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+    if parsed.input.is_some() && parsed.days.as_ref().is_none_or(|d| d.len() > 1) {
+        return Err("--input can only be used with a single day".into());
+    }
+    Ok(parsed)
+}
+
+pub fn input_path(args: &Args, day: u32) -> String {
+    args.input.clone().unwrap_or_else(|| format!("day{day:02}.input"))
+}
+
+pub fn read_lines(path: &str) -> io::Result<Vec<String>> {
+    if path == "-" {
+        io::stdin().lock().lines().collect()
+    } else {
+        BufReader::new(File::open(path)?).lines().collect()
+    }
+}
+
+pub fn dialect_for(day: u32) -> Option<Dialect> {
+    match day {
+        18 => Some(Dialect::Duet),
+        23 => Some(Dialect::Coprocessor),
+        _ => None,
+    }
+}
+
+// Programs are read with the assembler, so hand-written ones can use
+// labels, comments and longer register names.
+pub fn load_program(path: &str, dialect: Dialect) -> Result<Assembly, String> {
+    let lines = read_lines(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    assemble(&lines, dialect).map_err(|e| format!("{path}: {e}"))
+}
+
+// The VM program for the one day named on the command line, run through
+// the optimizer if --optimize was given. Errors are reported here.
+pub fn day_program(args: &Args, cmd: &str) -> Result<Assembly, ExitCode> {
+    let Some(&[day]) = args.days.as_deref() else {
+        eprintln!("{cmd} needs a single day (18 or 23)");
+        return Err(ExitCode::from(2));
+    };
+    let Some(dialect) = dialect_for(day) else {
+        eprintln!("day {day} has no VM program");
+        return Err(ExitCode::from(2));
+    };
+    let mut asm = load_program(&input_path(args, day), dialect).map_err(|e| {
+        eprintln!("{e}");
+        ExitCode::FAILURE
+    })?;
+    if let Some((name, _)) = args.regs.iter().find(|(name, _)| asm.register(name).is_none()) {
+        eprintln!("--reg: the program has no register {name}");
+        return Err(ExitCode::from(2));
+    }
+    if args.optimize {
+        asm.program = optimize(&asm.program).0;
+    }
+    Ok(asm)
+}
+
+// A VM for the program with any --reg and --arith values applied
+pub fn new_vm<'a>(asm: &'a Assembly, args: &Args) -> VM<'a> {
+    let mut vm = VM::new(&asm.program);
+    vm.arithmetic = args.arith;
+    for (name, v) in &args.regs {
+        vm.set_reg(asm.register(name).unwrap(), *v);
+    }
+    vm
+}
@@ -0,0 +1,19 @@
+use std::process::ExitCode;
+use crate::args::{day_program, Args};
+
+// Prints the plain program, which day18/day23 can read as input, and
+// where each longer register name ended up. There's no --optimize here
+// since the optimizer's `composite` isn't part of either dialect.
+pub fn cmd_asm(args: &Args) -> ExitCode {
+    let asm = match day_program(args, "asm") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    for inst in &asm.program {
+        println!("{inst}");
+    }
+    for (name, r) in asm.registers.iter().filter(|(name, _)| name.len() > 1) {
+        eprintln!("{name} -> {r}");
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+use advent2017::bench::{compare, Baseline, Comparison, Stats};
+use advent2017::days;
+use crate::args::{input_path, read_lines, Args};
+
+// Times each part `args.runs` times; samples are keyed by part number.
+fn sample_day(args: &Args, day: u32, input: &[String]) -> Result<BTreeMap<u32, Vec<Duration>>, String> {
+    let mut samples: BTreeMap<u32, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..args.runs {
+        let result = days::run(day, input, &args.parts)
+            .unwrap()
+            .map_err(|e| e.to_string())?;
+        for p in result.parts {
+            samples.entry(p.part).or_default().push(p.elapsed);
+        }
+    }
+    Ok(samples)
+}
+
+pub fn cmd_bench(args: &Args) -> ExitCode {
+    let Some(day_list) = &args.days else {
+        eprintln!("missing day");
+        return ExitCode::from(2);
+    };
+    let previous = match &args.compare {
+        Some(path) => match Baseline::load(path) {
+            Ok(b) => Some(b),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            },
+        },
+        None => None,
+    };
+    // Saving merges into an existing file so single days can be re-measured.
+    let mut saved = match &args.save {
+        Some(path) if Path::new(path).exists() => match Baseline::load(path) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            },
+        },
+        _ => Baseline::default(),
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    println!("{:<12} {:>12} {:>12} {:>12}", format!("{} runs", args.runs), "min", "median", "max");
+    for &day in day_list {
+        let path = input_path(args, day);
+        let samples = match read_lines(&path) {
+            Ok(input) => sample_day(args, day, &input).map_err(|e| format!("{path}: {e}")),
+            Err(e) => Err(format!("cannot read {path}: {e}")),
+        };
+        let samples = match samples {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Day {day:02}: {e}");
+                status = ExitCode::FAILURE;
+                continue;
+            },
+        };
+        for (part, times) in samples {
+            let stats = Stats::from_samples(&times).unwrap();
+            print!(
+                "Day {day:02} p{part}   {:>12.2?} {:>12.2?} {:>12.2?}",
+                stats.min, stats.median, stats.max,
+            );
+            if let Some(prev) = &previous {
+                match compare(prev.get(day, part), stats.median, args.threshold) {
+                    Comparison::New => print!("  (no baseline)"),
+                    Comparison::Ok(ratio) => print!("  {ratio:.2}x"),
+                    Comparison::Regressed(ratio) => {
+                        print!("  {ratio:.2}x REGRESSED");
+                        status = ExitCode::FAILURE;
+                    },
+                }
+            }
+            println!();
+            saved.set(day, part, stats.median);
+        }
+    }
+    if let Some(path) = &args.save {
+        if let Err(e) = saved.save(path) {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    status
+}
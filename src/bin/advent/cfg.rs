@@ -0,0 +1,12 @@
+use std::process::ExitCode;
+use advent2017::vm::Cfg;
+use crate::args::{day_program, Args};
+
+pub fn cmd_cfg(args: &Args) -> ExitCode {
+    let asm = match day_program(args, "cfg") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    print!("{}", Cfg::new(&asm.program).dot());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,23 @@
+use std::io;
+use std::process::ExitCode;
+use advent2017::vm::Debugger;
+use crate::args::{day_program, new_vm, Args};
+
+pub fn cmd_debug(args: &Args) -> ExitCode {
+    if args.input.as_deref() == Some("-") {
+        eprintln!("debugger commands are read from stdin; the program must come from a file");
+        return ExitCode::from(2);
+    }
+    let asm = match day_program(args, "debug") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let mut debugger = Debugger::new(new_vm(&asm, args), args.trace).with_registers(asm.registers.clone());
+    match debugger.run(io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,12 @@
+use std::process::ExitCode;
+use advent2017::vm::{decompile, disassemble};
+use crate::args::{day_program, Args};
+
+pub fn cmd_disasm(args: &Args) -> ExitCode {
+    let asm = match day_program(args, "disasm") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    print!("{}\n{}", disassemble(&asm.program), decompile(&asm.program));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,132 @@
+use std::process::ExitCode;
+use std::vec::Vec;
+
+mod args;
+mod asm;
+mod bench;
+mod cfg;
+mod debug;
+mod disasm;
+mod network;
+mod profile;
+mod run;
+mod symbolic;
+mod trace;
+mod verify;
+
+use args::{parse_args, Args};
+
+const USAGE: &str = "\
+usage: advent run <day|all> [--part 1|2] [--input FILE] [--format text|json]
+       advent verify [<day|all>] [--part 1|2] [--input FILE] [--answers FILE]
+       advent bench <day|all> [--part 1|2] [--input FILE] [--runs N]
+                    [--save FILE] [--compare FILE] [--threshold PCT]
+       advent debug <18|23> [--input FILE] [--trace N] [--optimize]
+                    [--reg R=V]... [--arith MODE]
+       advent disasm <18|23> [--input FILE] [--optimize]
+       advent cfg <18|23> [--input FILE] [--optimize]
+       advent network 18 [--input FILE] [--programs N] [--topology T]
+                    [--threads [--capacity N]] [--arith MODE]
+       advent record <18|23> [--input FILE] [--reg R=V]... [--optimize]
+                    [--arith MODE] [--limit N] [--output FILE]
+       advent replay <18|23> [--input FILE] [--reg R=V]... [--optimize]
+                    [--arith MODE] < TRACE
+       advent trace-diff TRACE TRACE
+       advent asm <18|23> [--input FILE]
+       advent profile <18|23> [--input FILE] [--reg R=V]... [--optimize]
+                    [--arith MODE] [--limit N]
+       advent symbolic 23 [--input FILE] [--at PC]
+
+  <day>           day number, 1-25
+  all             every day in order
+  --part N        only part N
+  --input FILE    read input from FILE ('-' for stdin) instead of dayNN.input
+  --format json   print one JSON record per answer:
+                  {\"day\":N,\"part\":N,\"answer\":...,\"elapsed_ns\":N}
+  --answers FILE  expected answers (default answers.toml), with one
+                  [dayNN] table per day holding part1 and part2
+  --runs N        time each part N times (default 10)
+  --save FILE     record median timings in FILE, merging with its contents
+  --compare FILE  compare medians against FILE and fail on a regression
+  --threshold PCT slowdown tolerated by --compare (default 10)
+  --trace N       instructions kept for the debugger's trace (default 20)
+  --programs N    number of duet programs to connect (default 2)
+  --topology T    ring (default), broadcast, or links like 0-1,1-2,2-0
+  --threads       run each program on its own thread
+  --capacity N    values each program's channel holds with --threads
+                  (default 1024)
+  --reg R=V       start with register R set to V (repeatable); R can be
+                  any register name the program uses
+  --optimize      run the program through the loop optimizer first
+  --limit N       stop recording or profiling after N instructions
+  --output FILE   write the trace to FILE instead of stdout
+  --at PC         where symbolic stops (default the first loop head)
+  --arith MODE    what add, sub, mul and mod do on overflow: checked
+                  (default) stops with a fault, wrapping wraps around,
+                  saturating clamps; mod by zero always faults
+
+Programs for 18 and 23 may use labels (loop: ... jnz g loop), comments
+after ; or #, and longer register names; asm prints the plain form.
+cfg prints the control-flow graph as Graphviz source (pipe it to dot -Tsvg).
+debug reads commands from stdin; type help for a list. replay re-runs a
+recorded trace, feeding back the values it received, and reports the
+first step that differs; trace-diff compares two recordings. profile
+prints per-opcode counts, the busiest loops and the listing with hit counts.
+symbolic runs day 23's setup code with a left unknown and prints each
+register as a formula of it, one block per path taken.";
+
+fn with_args(args: &[String], allowed: &[&str], cmd: fn(&Args) -> ExitCode) -> ExitCode {
+    match parse_args(args, allowed) {
+        Ok(a) => cmd(&a),
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            ExitCode::from(2)
+        },
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        Some("run") => with_args(&args[1..], &["--part", "--input", "--format"], run::cmd_run),
+        Some("verify") => with_args(&args[1..], &["--part", "--input", "--answers"], verify::cmd_verify),
+        Some("bench") => with_args(
+            &args[1..],
+            &["--part", "--input", "--runs", "--save", "--compare", "--threshold"],
+            bench::cmd_bench,
+        ),
+        Some("debug") => with_args(&args[1..], &["--input", "--trace", "--optimize", "--reg", "--arith"], debug::cmd_debug),
+        Some("disasm") => with_args(&args[1..], &["--input", "--optimize"], disasm::cmd_disasm),
+        Some("cfg") => with_args(&args[1..], &["--input", "--optimize"], cfg::cmd_cfg),
+        Some("network") => with_args(
+            &args[1..],
+            &["--input", "--programs", "--topology", "--threads", "--capacity", "--arith"],
+            network::cmd_network,
+        ),
+        Some("record") => with_args(
+            &args[1..],
+            &["--input", "--reg", "--optimize", "--arith", "--limit", "--output"],
+            trace::cmd_record,
+        ),
+        Some("replay") => with_args(&args[1..], &["--input", "--reg", "--optimize", "--arith"], trace::cmd_replay),
+        Some("asm") => with_args(&args[1..], &["--input"], asm::cmd_asm),
+        Some("profile") => with_args(
+            &args[1..],
+            &["--input", "--reg", "--optimize", "--arith", "--limit"],
+            profile::cmd_profile,
+        ),
+        Some("symbolic") => with_args(&args[1..], &["--input", "--at"], symbolic::cmd_symbolic),
+        Some("trace-diff") => trace::cmd_trace_diff(&args[1..]).unwrap_or_else(|| {
+            eprintln!("trace-diff needs two trace files\n\n{USAGE}");
+            ExitCode::from(2)
+        }),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        },
+    }
+}
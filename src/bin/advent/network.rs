@@ -0,0 +1,39 @@
+use std::process::ExitCode;
+use advent2017::vm::{run_threaded, Dialect, Network};
+use crate::args::{input_path, load_program, Args};
+
+pub fn cmd_network(args: &Args) -> ExitCode {
+    if args.days.as_deref() != Some(&[18]) {
+        eprintln!("network only runs day 18's duet program");
+        return ExitCode::from(2);
+    }
+    let path = input_path(args, 18);
+    let program = match load_program(&path, Dialect::Duet) {
+        Ok(asm) => asm.program,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        },
+    };
+    let report = if args.threads {
+        run_threaded(&program, args.programs, &args.topology, args.capacity, args.arith)
+    } else {
+        Network::new(&program, args.programs, &args.topology).map(|mut net| {
+            for vm in &mut net.vms {
+                vm.arithmetic = args.arith;
+            }
+            net.run();
+            net.report()
+        })
+    };
+    match report {
+        Ok(r) => {
+            print!("{r}");
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        },
+    }
+}
@@ -0,0 +1,32 @@
+use std::process::ExitCode;
+use advent2017::vm::{annotate, profile, End};
+use crate::args::{day_program, new_vm, Args};
+
+pub fn cmd_profile(args: &Args) -> ExitCode {
+    let asm = match day_program(args, "profile") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let p = profile(&mut new_vm(&asm, args), args.limit);
+    let pct = |n: u64| n as f64 * 100.0 / p.steps.max(1) as f64;
+    let end = match p.end {
+        End::Halted => "halted".to_string(),
+        End::Blocked(r) => format!("blocked on rcv {r}"),
+        End::Fault { pc, kind } => format!("{kind} at pc {pc}: {}", asm.program[pc as usize]),
+        End::Limit => "hit --limit".to_string(),
+    };
+    println!("{} steps, {end}", p.steps);
+    println!("\nby opcode:");
+    for (op, n) in p.by_opcode(&asm.program) {
+        println!("  {:<10} {n:>12} {:>5.1}%", op.name(), pct(n));
+    }
+    println!("\nhot loops:");
+    for l in p.hot_loops() {
+        println!(
+            "  pc {:>3}-{:<3} {:>12} iterations {:>12} steps {:>5.1}%",
+            l.start, l.end, l.iterations, l.steps, pct(l.steps),
+        );
+    }
+    print!("\n{}", annotate(&asm.program, &p));
+    ExitCode::SUCCESS
+}
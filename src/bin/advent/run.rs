@@ -0,0 +1,60 @@
+use std::process::ExitCode;
+use std::time::Duration;
+use advent2017::days;
+use advent2017::runner::DayResult;
+use advent2017::solution::json_string;
+use crate::args::{input_path, read_lines, Args, Format};
+
+fn print_result(result: &DayResult, format: Format) {
+    if format == Format::Json {
+        for p in &result.parts {
+            println!("{}", p.to_json(result.day));
+        }
+        return;
+    }
+    println!("Day {:02}  (parse {:.2?})", result.day, result.parse_elapsed);
+    for p in &result.parts {
+        println!("  Part {}: {:<40} {:>12.2?}", p.part, p.answer.to_string(), p.elapsed);
+    }
+}
+
+fn report_error(day: u32, msg: &str, format: Format) {
+    match format {
+        Format::Text => eprintln!("Day {day:02}: {msg}"),
+        Format::Json => println!("{{\"day\":{day},\"error\":{}}}", json_string(msg)),
+    }
+}
+
+pub fn cmd_run(args: &Args) -> ExitCode {
+    let Some(day_list) = &args.days else {
+        eprintln!("missing day");
+        return ExitCode::from(2);
+    };
+    let mut total = Duration::ZERO;
+    let mut status = ExitCode::SUCCESS;
+    for &day in day_list {
+        let path = input_path(args, day);
+        let input = match read_lines(&path) {
+            Ok(i) => i,
+            Err(e) => {
+                report_error(day, &format!("cannot read {path}: {e}"), args.format);
+                status = ExitCode::FAILURE;
+                continue;
+            },
+        };
+        match days::run(day, &input, &args.parts).unwrap() {
+            Ok(result) => {
+                print_result(&result, args.format);
+                total += result.elapsed();
+            },
+            Err(e) => {
+                report_error(day, &format!("{path}: {e}"), args.format);
+                status = ExitCode::FAILURE;
+            },
+        }
+    }
+    if day_list.len() > 1 && args.format == Format::Text {
+        println!("Total: {total:.2?}");
+    }
+    status
+}
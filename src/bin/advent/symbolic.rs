@@ -0,0 +1,46 @@
+use std::process::ExitCode;
+use advent2017::vm::{symbolic, Instruction, RI};
+use crate::args::{day_program, Args};
+
+// The first loop head, where the setup code has finished
+fn first_loop(program: &[Instruction]) -> Option<usize> {
+    program.iter()
+        .enumerate()
+        .filter_map(|(pc, inst)| match *inst {
+            Instruction::Jgz(_, RI::Imm(off)) | Instruction::Jnz(_, RI::Imm(off)) if off <= 0 => {
+                usize::try_from(pc as i64 + off).ok()
+            },
+            _ => None,
+        })
+        .min()
+}
+
+pub fn cmd_symbolic(args: &Args) -> ExitCode {
+    // Day 18 starts with a jump by p itself (jgz p p), which can't be
+    // followed symbolically, so only day 23 is accepted
+    if args.days.as_deref().is_some_and(|d| d != [23]) {
+        eprintln!("symbolic only works on day 23");
+        return ExitCode::from(2);
+    }
+    let asm = match day_program(args, "symbolic") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let Some(at) = args.at.or_else(|| first_loop(&asm.program)) else {
+        eprintln!("symbolic: the program has no loop; pass --at PC");
+        return ExitCode::FAILURE;
+    };
+    match symbolic(&asm.program, &['a'], at) {
+        Ok(branches) => {
+            println!("at pc {at}:");
+            for b in branches {
+                print!("{b}");
+            }
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("symbolic: {e}");
+            ExitCode::FAILURE
+        },
+    }
+}
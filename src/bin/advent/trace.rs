@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+use advent2017::vm::{diff_traces, record, replay, Trace};
+use crate::args::{day_program, new_vm, Args};
+
+pub fn cmd_record(args: &Args) -> ExitCode {
+    let asm = match day_program(args, "record") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let trace = record(&mut new_vm(&asm, args), args.limit);
+    match &args.output {
+        Some(path) => {
+            if let Err(e) = fs::write(path, trace.to_string()) {
+                eprintln!("cannot write {path}: {e}");
+                return ExitCode::FAILURE;
+            }
+            eprintln!("recorded {} steps to {path}", trace.events.len());
+        },
+        None => print!("{trace}"),
+    }
+    ExitCode::SUCCESS
+}
+
+fn parse_trace(name: &str, text: &str) -> Result<Trace, ExitCode> {
+    text.parse::<Trace>().map_err(|e| {
+        eprintln!("{name}: {e}");
+        ExitCode::FAILURE
+    })
+}
+
+pub fn cmd_replay(args: &Args) -> ExitCode {
+    if args.input.as_deref() == Some("-") {
+        eprintln!("the trace is read from stdin; the program must come from a file");
+        return ExitCode::from(2);
+    }
+    let asm = match day_program(args, "replay") {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let mut text = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut text) {
+        eprintln!("cannot read trace: {e}");
+        return ExitCode::FAILURE;
+    }
+    let trace = match parse_trace("stdin", &text) {
+        Ok(t) => t,
+        Err(code) => return code,
+    };
+    match replay(&trace, &mut new_vm(&asm, args)) {
+        Ok(()) => {
+            println!("replayed {} steps", trace.events.len());
+            ExitCode::SUCCESS
+        },
+        Err(d) => {
+            println!("{d}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+// Takes two file names rather than the usual day arguments.
+pub fn cmd_trace_diff(args: &[String]) -> Option<ExitCode> {
+    let [a, b] = args else {
+        return None;
+    };
+    let mut traces = Vec::new();
+    for path in [a, b] {
+        let text = match fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("cannot read {path}: {e}");
+                return Some(ExitCode::FAILURE);
+            },
+        };
+        match parse_trace(path, &text) {
+            Ok(t) => traces.push(t),
+            Err(code) => return Some(code),
+        }
+    }
+    match diff_traces(&traces[0], &traces[1]) {
+        None => {
+            println!("traces match ({} steps)", traces[0].events.len());
+            Some(ExitCode::SUCCESS)
+        },
+        Some(d) => {
+            println!("{d}");
+            Some(ExitCode::FAILURE)
+        },
+    }
+}
@@ -0,0 +1,96 @@
+use std::panic;
+use std::process::ExitCode;
+use advent2017::days;
+use advent2017::verify::{check, check_missing, diff, ExpectedAnswers, Verdict};
+use crate::args::{input_path, read_lines, Args};
+
+#[derive(Default)]
+struct Tally {
+    pass: usize,
+    mismatch: usize,
+    fail: usize,
+    unchecked: usize,
+}
+
+fn indent(s: &str) -> String {
+    s.lines().map(|l| format!("    {l}\n")).collect()
+}
+
+pub fn cmd_verify(args: &Args) -> ExitCode {
+    let answers = match ExpectedAnswers::load(&args.answers) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        },
+    };
+    let day_list = args.days.clone().unwrap_or_else(|| answers.days());
+    let mut tally = Tally::default();
+    for day in day_list {
+        let path = input_path(args, day);
+        let input = match read_lines(&path) {
+            Ok(i) => i,
+            Err(e) => {
+                println!("Day {day:02}: FAIL  cannot read {path}: {e}");
+                tally.fail += 1;
+                continue;
+            },
+        };
+        let result = match panic::catch_unwind(|| days::run(day, &input, &args.parts)) {
+            Ok(Some(Ok(r))) => r,
+            Ok(None) => {
+                println!("Day {day:02}: FAIL  no solution for day {day}");
+                tally.fail += 1;
+                continue;
+            },
+            Ok(Some(Err(e))) => {
+                println!("Day {day:02}: FAIL  {path}: {e}");
+                tally.fail += 1;
+                continue;
+            },
+            Err(_) => {
+                println!("Day {day:02}: FAIL  panicked");
+                tally.fail += 1;
+                continue;
+            },
+        };
+        let missing = args.parts.iter()
+            .filter(|part| result.parts.iter().all(|p| p.part != **part))
+            .filter_map(|part| check_missing(answers.get(day, *part)).map(|v| (*part, v)));
+        let verdicts = result.parts.iter()
+            .map(|p| (p.part, check(answers.get(day, p.part), &p.answer)))
+            .chain(missing)
+            .collect::<Vec<_>>();
+        for (part, verdict) in verdicts {
+            match verdict {
+                Verdict::Pass => {
+                    println!("Day {day:02} part {part}: pass");
+                    tally.pass += 1;
+                },
+                Verdict::Mismatch { expected, actual } => {
+                    println!("Day {day:02} part {part}: MISMATCH");
+                    print!("{}", indent(&diff(&expected, &actual)));
+                    tally.mismatch += 1;
+                },
+                Verdict::Unchecked(actual) => {
+                    println!("Day {day:02} part {part}: unchecked ({actual})");
+                    tally.unchecked += 1;
+                },
+                Verdict::Missing(expected) => {
+                    println!("Day {day:02} part {part}: MISMATCH");
+                    print!("{}", indent(&format!("expected: {expected}\nactual:   (no answer)")));
+                    tally.mismatch += 1;
+                },
+            }
+        }
+    }
+    println!(
+        "{} passed, {} mismatched, {} failed, {} unchecked",
+        tally.pass, tally.mismatch, tally.fail, tally.unchecked,
+    );
+    if tally.mismatch > 0 || tally.fail > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,142 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::ExitCode;
+use std::vec::Vec;
+use advent2017::{Digest, KnotHasher};
+
+const USAGE: &str = "\
+usage: knothash [FILE]...
+       knothash --check [--quiet] [FILE]...
+
+Prints the knot hash of each FILE, or of stdin when there are none or
+FILE is '-', as 32 hex digits followed by two spaces and the name.
+
+  -c, --check   read digests and names from each FILE, in the format
+                knothash prints, and check every named file matches
+  -q, --quiet   with --check, only report files that fail";
+
+fn open(path: &str) -> io::Result<Box<dyn Read>> {
+    if path == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+fn hash(path: &str) -> io::Result<Digest> {
+    let mut hasher = KnotHasher::new();
+    io::copy(&mut open(path)?, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+// `DIGEST  NAME`, also accepting the ` *NAME` binary marker sha256sum
+// writes
+fn parse_line(line: &str) -> Option<(Digest, &str)> {
+    let (digest, name) = line.split_once(' ')?;
+    let name = name.strip_prefix(' ').or_else(|| name.strip_prefix('*'))?;
+    if name.is_empty() {
+        return None;
+    }
+    Some((digest.parse().ok()?, name))
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{n} {}", if n == 1 { one } else { many })
+}
+
+fn print_digests(files: &[String]) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+    for path in files {
+        match hash(path) {
+            Ok(d) => println!("{d}  {path}"),
+            Err(e) => {
+                eprintln!("knothash: {path}: {e}");
+                code = ExitCode::FAILURE;
+            },
+        }
+    }
+    code
+}
+
+fn check(lists: &[String], quiet: bool) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+    for list in lists {
+        let lines: Vec<String> = match open(list).and_then(|r| BufReader::new(r).lines().collect()) {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("knothash: {list}: {e}");
+                code = ExitCode::FAILURE;
+                continue;
+            },
+        };
+        let (mut malformed, mut unreadable, mut mismatched, mut checked) = (0, 0, 0, 0);
+        for line in lines.iter().filter(|l| !l.trim().is_empty()) {
+            let Some((expected, name)) = parse_line(line) else {
+                malformed += 1;
+                continue;
+            };
+            checked += 1;
+            match hash(name) {
+                Ok(d) if d == expected => {
+                    if !quiet {
+                        println!("{name}: OK");
+                    }
+                },
+                Ok(_) => {
+                    println!("{name}: FAILED");
+                    mismatched += 1;
+                },
+                Err(e) => {
+                    eprintln!("knothash: {name}: {e}");
+                    println!("{name}: FAILED open or read");
+                    unreadable += 1;
+                },
+            }
+        }
+        if malformed > 0 {
+            eprintln!("knothash: WARNING: {} improperly formatted", plural(malformed, "line is", "lines are"));
+        }
+        if unreadable > 0 {
+            eprintln!("knothash: WARNING: {} could not be read", plural(unreadable, "listed file", "listed files"));
+        }
+        if mismatched > 0 {
+            eprintln!("knothash: WARNING: {} did NOT match", plural(mismatched, "computed checksum", "computed checksums"));
+        }
+        if checked == 0 {
+            eprintln!("knothash: {list}: no properly formatted checksum lines found");
+        }
+        if checked == 0 || unreadable + mismatched > 0 {
+            code = ExitCode::FAILURE;
+        }
+    }
+    code
+}
+
+fn main() -> ExitCode {
+    let mut check_mode = false;
+    let mut quiet = false;
+    let mut files = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-c" | "--check" => check_mode = true,
+            "-q" | "--quiet" => quiet = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            },
+            a if a.starts_with('-') && a != "-" => {
+                eprintln!("unexpected option: {a}\n\n{USAGE}");
+                return ExitCode::from(2);
+            },
+            _ => files.push(arg),
+        }
+    }
+    if quiet && !check_mode {
+        eprintln!("--quiet only applies with --check\n\n{USAGE}");
+        return ExitCode::from(2);
+    }
+    if files.is_empty() {
+        files.push("-".to_string());
+    }
+    if check_mode { check(&files, quiet) } else { print_digests(&files) }
+}
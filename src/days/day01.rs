@@ -0,0 +1,80 @@
+use std::vec::Vec;
+use itertools::Itertools;
+use crate::parse::{first_line, ParseError};
+use crate::solution::{Answer, Solution};
+
+fn part1(nums: &[i64]) -> i64 {
+    nums
+        .iter()
+        .circular_tuple_windows()
+        .fold(0, |sum, (a, b)| if a == b { sum + a } else { sum })
+}
+
+fn part2(nums: &[i64]) -> i64 {
+    let mid = nums.len() / 2;
+    nums.iter()
+        .enumerate()
+        .fold(0, |sum, (idx, n)| {
+            if nums[(idx + mid) % nums.len()] == *n {
+                sum + n
+            } else {
+                sum
+            }
+        })
+}
+
+fn setup(input: &str) -> Result<Vec<i64>, ParseError> {
+    input
+        .char_indices()
+        .map(|(idx, c)| c.to_digit(10)
+            .map(|d| d as i64)
+            .ok_or_else(|| ParseError::new(input, &input[idx..idx + c.len_utf8()], "digit"))
+        )
+        .collect()
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i64>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        setup(first_line(input)?).map_err(|e| e.at_line(1))
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day01_test() {
+        let nums = setup("1122").unwrap();
+        assert_eq!(part1(&nums), 3);
+        let nums = setup("1111").unwrap();
+        assert_eq!(part1(&nums), 4);
+        let nums = setup("1234").unwrap();
+        assert_eq!(part1(&nums), 0);
+        let nums = setup("91212129").unwrap();
+        assert_eq!(part1(&nums), 9);
+        let nums = setup("1212").unwrap();
+        assert_eq!(part2(&nums), 6);
+        let nums = setup("1221").unwrap();
+        assert_eq!(part2(&nums), 0);
+        let nums = setup("123425").unwrap();
+        assert_eq!(part2(&nums), 4);
+        let nums = setup("123123").unwrap();
+        assert_eq!(part2(&nums), 12);
+        let nums = setup("12131415").unwrap();
+        assert_eq!(part2(&nums), 4);
+
+        let err = Day01::parse(&["12x4".to_string()]).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), 3));
+        assert_eq!(err.to_string(), "line 1, column 3: expected digit, found \"x\"");
+    }
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+use std::vec::Vec;
+use itertools::Itertools;
+use itertools::MinMaxResult::MinMax;
+use crate::parse::{parse_lines, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Row(Vec<i64>);
+impl FromStr for Row {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split('\t')
+            .map(|n| parse_token(s, n, "integer"))
+            .collect::<Result<Vec<i64>, _>>()
+            .map(Row)
+    }
+}
+
+fn part1(input: &[Row]) -> i64 {
+    input
+        .iter()
+        .map(|row| match row.0.iter().minmax() {
+            MinMax(&min, &max) => max - min,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn part2(input: &[Row]) -> i64 {
+    input
+        .iter()
+        .flat_map(|row|
+            row.0.iter()
+            .sorted()
+            .tuple_combinations()
+            .find(|(&a, &b)| b % a == 0)
+            .map(|(&a, &b)| b / a)
+        )
+        .sum()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Row>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_lines(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day02_test() {
+        let input: Vec<Row> = test_input(include_str!("day02.testinput"));
+        assert_eq!(part1(&input), 18);
+        let input: Vec<Row> = test_input(include_str!("day02.testinput2"));
+        assert_eq!(part2(&input), 9);
+    }
+}
@@ -0,0 +1,90 @@
+use itertools::Itertools;
+use ya_advent_lib::infinite_grid::InfiniteGrid;
+use crate::parse::{first_line, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+fn calc(target: i64) -> (i64, i64) {
+    if target <= 1 {
+        return (0, 0);
+    }
+    let mut counter = 9;
+    let mut prev_counter = 1;
+    let mut level = 1;
+
+    while counter < target {
+        let outer = counter - prev_counter;
+        let addl = (outer / 4 + 2) * 4;
+        prev_counter = counter;
+        counter += addl;
+        level += 1;
+    }
+    let outer = counter - prev_counter;
+    let offset = target - prev_counter - 1;
+    let side_len = outer / 4;
+    let side_offset = 1 - side_len / 2 + (offset % side_len);
+    match offset / side_len {
+        0 => (level, side_offset),
+        1 => (-side_offset, level),
+        2 => (-level, -side_offset),
+        3 => (side_offset, -level),
+        _ => panic!(),
+    }
+}
+
+fn part1(input: i64) -> i64 {
+    let (x, y) = calc(input);
+    //println!("input: {input} coord: {x}, {y}");
+    x.abs() + y.abs()
+}
+
+fn part2(input: i64) -> i64 {
+    let mut grid: InfiniteGrid<i64> = InfiniteGrid::new(0);
+    grid.set(0, 0, 1);
+    let mut lastnum = 1;
+    let mut index = 2;
+    while lastnum <= input {
+        let (x, y) = calc(index);
+        let sum: i64 = (-1..=1).cartesian_product(-1..=1)
+            .filter(|(xo, yo)| *xo != 0 || *yo != 0)
+            .map(|(xo, yo)| grid.get(x + xo, y + yo))
+            .sum();
+        grid.set(x, y, sum);
+        lastnum = sum;
+        index += 1;
+    }
+    lastnum
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = i64;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        let line = first_line(input)?;
+        parse_token(line, line, "integer").map_err(|e| e.at_line(1))
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(*input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(*input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day03_test() {
+        assert_eq!(part1(1), 0);
+        assert_eq!(part1(12), 3);
+        assert_eq!(part1(23), 2);
+        assert_eq!(part1(22), 3);
+        assert_eq!(part1(16), 3);
+        assert_eq!(part1(15), 2);
+        assert_eq!(part1(1024), 31);
+        assert_eq!(part2(10), 11);
+        assert_eq!(part2(60), 122);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::vec::Vec;
+use itertools::Itertools;
+use crate::parse::{parse_lines, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Passphrase(Vec<String>);
+impl FromStr for Passphrase {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Passphrase(s.split(' ').map(|s| s.to_string()).collect()))
+    }
+}
+impl Passphrase {
+    fn is_valid(&self) -> bool {
+        let set: HashSet<String> = HashSet::from_iter(self.0.iter().cloned());
+        set.len() == self.0.len()
+    }
+    fn is_valid2(&self) -> bool {
+        let set: HashSet<String> = HashSet::from_iter(
+            self.0.iter().map(|s| s.chars().sorted().collect::<String>())
+        );
+        set.len() == self.0.len()
+    }
+}
+
+fn part1(input: &[Passphrase]) -> usize {
+    input.iter().filter(|p| p.is_valid()).count()
+}
+
+fn part2(input: &[Passphrase]) -> usize {
+    input.iter().filter(|p| p.is_valid2()).count()
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Passphrase>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_lines(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day04_test() {
+        let p = "aa bb cc dd ee".parse::<Passphrase>().unwrap();
+        assert!(p.is_valid());
+        let p = "aa bb cc dd aa".parse::<Passphrase>().unwrap();
+        assert!(!p.is_valid());
+        let p = "aa bb cc dd aaa".parse::<Passphrase>().unwrap();
+        assert!(p.is_valid());
+        let p = "abcde fghij".parse::<Passphrase>().unwrap();
+        assert!(p.is_valid2());
+        let p = "abcde xyz ecdab".parse::<Passphrase>().unwrap();
+        assert!(!p.is_valid2());
+        let p = "a ab abc abd abf abj".parse::<Passphrase>().unwrap();
+        assert!(p.is_valid2());
+        let p = "iiii oiii ooii oooi oooo".parse::<Passphrase>().unwrap();
+        assert!(p.is_valid2());
+        let p = "oiii ioii iioi iiio".parse::<Passphrase>().unwrap();
+        assert!(!p.is_valid2());
+    }
+}
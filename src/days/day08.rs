@@ -0,0 +1,739 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Gt,
+    Lt,
+    Gte,
+    Lte,
+    Eq,
+    Neq,
+}
+impl FromStr for Op {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ">" => Ok(Op::Gt),
+            ">=" => Ok(Op::Gte),
+            "<" => Ok(Op::Lt),
+            "<=" => Ok(Op::Lte),
+            "==" => Ok(Op::Eq),
+            "!=" => Ok(Op::Neq),
+            _ => Err(ParseError::new(s, s, "comparison operator")),
+        }
+    }
+}
+impl Op {
+    fn negate(self) -> Self {
+        match self {
+            Op::Gt => Op::Lte,
+            Op::Lte => Op::Gt,
+            Op::Lt => Op::Gte,
+            Op::Gte => Op::Lt,
+            Op::Eq => Op::Neq,
+            Op::Neq => Op::Eq,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Arith {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+// Registers are slots in the register file, numbered in order of first
+// appearance so the VM can keep them in a Vec instead of looking names
+// up on every instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Expr {
+    Num(i64),
+    Reg(usize),
+    Neg(Box<Expr>),
+    Bin(Arith, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Cond {
+    Cmp(Expr, Op, Expr),
+    And(Box<Cond>, Box<Cond>),
+    Or(Box<Cond>, Box<Cond>),
+    Not(Box<Cond>),
+}
+
+// `dec` is stored as adding the negated amount
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Stmt {
+    Update(usize, Expr),
+    Block(Vec<Stmt>),
+    If(Cond, Box<Stmt>, Option<Box<Stmt>>),
+}
+
+pub struct Program {
+    code: Vec<Code>,
+    // Indexed by slot
+    names: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Num(i64),
+    Name,
+    Sym,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    kind: Kind,
+    // 0-based
+    line: usize,
+}
+
+// How deep blocks, conditions and expressions may nest, including the
+// length of an operator chain, which builds just as deep a tree. The
+// parser, compiler and drop all recurse that far.
+const MAX_DEPTH: usize = 256;
+
+const KEYWORDS: [&str; 7] = ["if", "else", "and", "or", "not", "inc", "dec"];
+const SYMBOLS: [&str; 15] = ["<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "%", "(", ")", "{", "}"];
+
+// Splits the whole input into numbers, names and symbols. Line breaks
+// carry no meaning, so blocks can span lines.
+fn tokenize(lines: &[String]) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut tokens = Vec::new();
+    for (line, text) in lines.iter().enumerate() {
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let start = text.len() - rest.len();
+            let first = rest.chars().next().unwrap();
+            let len = if first.is_ascii_digit() {
+                rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len())
+            } else if first.is_ascii_alphabetic() || first == '_' {
+                rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len())
+            } else {
+                match SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+                    Some(s) => s.len(),
+                    None => {
+                        let token = &text[start..start + first.len_utf8()];
+                        return Err(ParseError::new(text, token, "expression").at_line(line + 1));
+                    },
+                }
+            };
+            let token = &text[start..start + len];
+            let kind = if first.is_ascii_digit() {
+                Kind::Num(token.parse::<i64>()
+                    .map_err(|_| ParseError::new(text, token, "64-bit integer").at_line(line + 1))?)
+            } else if first.is_ascii_alphabetic() || first == '_' {
+                Kind::Name
+            } else {
+                Kind::Sym
+            };
+            tokens.push(Token { text: token, kind, line });
+            rest = rest[len..].trim_start();
+        }
+    }
+    Ok(tokens)
+}
+
+// Recursive descent over the tokens. A program is a sequence of
+// statements:
+//
+//   stmt := (reg inc|dec expr | '{' stmt* '}') [if cond [else stmt]]
+//   cond := cond or cond | cond and cond | not cond | (cond) | expr OP expr
+//   expr := expr +|- expr | expr *|/|% expr | -expr | (expr) | reg | N
+//
+// with the usual precedences, so the original `b inc 5 if a > 1` is
+// still a program of one statement.
+struct Parser<'a> {
+    lines: &'a [String],
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    slots: HashMap<&'a str, usize>,
+    names: Vec<String>,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|t| t.text)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let found = self.peek() == Some(text);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        match self.tokens.get(self.pos) {
+            Some(t) => ParseError::new(&self.lines[t.line], t.text, expected).at_line(t.line + 1),
+            None => {
+                let last = self.lines.len().max(1);
+                let text = self.lines.last().map_or("", |l| l.trim_end());
+                ParseError::eol(text, expected).at_line(last)
+            },
+        }
+    }
+
+    // One level deeper, at the current token
+    fn descend(&mut self) -> Result<(), ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("at most {MAX_DEPTH} levels of nesting")));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let base = self.depth;
+        self.descend()?;
+        let result = parse(self);
+        self.depth = base;
+        result
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), ParseError> {
+        if self.eat(text) { Ok(()) } else { Err(self.error(&format!("'{text}'"))) }
+    }
+
+    fn register(&mut self) -> Result<usize, ParseError> {
+        match self.tokens.get(self.pos) {
+            Some(t) if t.kind == Kind::Name && !KEYWORDS.contains(&t.text) => {
+                self.pos += 1;
+                let names = &mut self.names;
+                Ok(*self.slots.entry(t.text).or_insert_with(|| {
+                    names.push(t.text.to_string());
+                    names.len() - 1
+                }))
+            },
+            _ => Err(self.error("register")),
+        }
+    }
+
+    fn program(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut statements = Vec::new();
+        while self.pos < self.tokens.len() {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        let action = if self.eat("{") {
+            let mut body = Vec::new();
+            while !self.eat("}") {
+                if self.pos == self.tokens.len() {
+                    return Err(self.error("'}'"));
+                }
+                body.push(self.nested(Self::statement)?);
+            }
+            Stmt::Block(body)
+        } else {
+            let register = self.register()
+                .map_err(|_| self.error("register or '{'"))?;
+            let negate = match self.peek() {
+                Some("inc") => false,
+                Some("dec") => true,
+                _ => return Err(self.error("inc or dec")),
+            };
+            self.pos += 1;
+            let amount = self.expr()?;
+            Stmt::Update(register, if negate { negated(amount) } else { amount })
+        };
+        if !self.eat("if") {
+            return Ok(action);
+        }
+        let cond = self.or()?;
+        let otherwise = if self.eat("else") { Some(Box::new(self.nested(Self::statement)?)) } else { None };
+        Ok(Stmt::If(cond, Box::new(action), otherwise))
+    }
+
+    fn or(&mut self) -> Result<Cond, ParseError> {
+        let base = self.depth;
+        let mut cond = self.and()?;
+        while self.eat("or") {
+            self.descend()?;
+            cond = Cond::Or(Box::new(cond), Box::new(self.and()?));
+        }
+        self.depth = base;
+        Ok(cond)
+    }
+
+    fn and(&mut self) -> Result<Cond, ParseError> {
+        let base = self.depth;
+        let mut cond = self.not()?;
+        while self.eat("and") {
+            self.descend()?;
+            cond = Cond::And(Box::new(cond), Box::new(self.not()?));
+        }
+        self.depth = base;
+        Ok(cond)
+    }
+
+    fn not(&mut self) -> Result<Cond, ParseError> {
+        if self.eat("not") {
+            return Ok(Cond::Not(Box::new(self.nested(Self::not)?)));
+        }
+        // A parenthesis could open a condition or the left-hand
+        // expression of a comparison, so try the first and fall back to
+        // the second, reporting whichever got further on failure.
+        let start = self.pos;
+        let mut first = None;
+        if self.eat("(") {
+            match self.nested(Self::or).and_then(|c| self.expect(")").map(|_| c)) {
+                Ok(c) => return Ok(c),
+                Err(e) => first = Some(e),
+            }
+            self.pos = start;
+        }
+        self.comparison().map_err(|e| match first {
+            Some(f) if (f.line, f.column) > (e.line, e.column) => f,
+            _ => e,
+        })
+    }
+
+    fn comparison(&mut self) -> Result<Cond, ParseError> {
+        let lhs = self.expr()?;
+        let op = self.peek()
+            .and_then(|t| t.parse::<Op>().ok())
+            .ok_or_else(|| self.error("comparison operator"))?;
+        self.pos += 1;
+        Ok(Cond::Cmp(lhs, op, self.expr()?))
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let base = self.depth;
+        let mut expr = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some("+") => Arith::Add,
+                Some("-") => Arith::Sub,
+                _ => {
+                    self.depth = base;
+                    return Ok(expr);
+                },
+            };
+            self.descend()?;
+            self.pos += 1;
+            expr = Expr::Bin(op, Box::new(expr), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let base = self.depth;
+        let mut expr = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some("*") => Arith::Mul,
+                Some("/") => Arith::Div,
+                Some("%") => Arith::Mod,
+                _ => {
+                    self.depth = base;
+                    return Ok(expr);
+                },
+            };
+            self.descend()?;
+            self.pos += 1;
+            expr = Expr::Bin(op, Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("-") {
+            return Ok(negated(self.nested(Self::unary)?));
+        }
+        if self.eat("(") {
+            let expr = self.nested(Self::expr)?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        if let Some(Token { kind: Kind::Num(n), .. }) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            return Ok(Expr::Num(*n));
+        }
+        self.register().map(Expr::Reg).map_err(|_| self.error("expression"))
+    }
+}
+
+// Folds constants so `dec 5` and `-10` stay plain numbers
+fn negated(expr: Expr) -> Expr {
+    match expr {
+        Expr::Num(n) if n != i64::MIN => Expr::Num(-n),
+        e => Expr::Neg(Box::new(e)),
+    }
+}
+
+fn parse_program(lines: &[String]) -> Result<Program, ParseError> {
+    let mut parser = Parser {
+        lines,
+        tokens: tokenize(lines)?,
+        pos: 0,
+        slots: HashMap::new(),
+        names: Vec::new(),
+        depth: 0,
+    };
+    let statements = parser.program()?;
+    Ok(compile(&statements, parser.names))
+}
+
+// Stack machine code. Jumps hold label numbers while compiling and
+// code offsets once it's done.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Code {
+    Push(i64),
+    Load(usize),
+    Neg,
+    Arith(Arith),
+    // Pops two values and jumps unless they compare
+    JumpUnless(Op, usize),
+    Jump(usize),
+    // Pops a value and adds it to the register
+    Add(usize),
+    // The end of a top-level statement
+    Step,
+}
+
+struct Compiler {
+    code: Vec<Code>,
+    labels: Vec<usize>,
+}
+
+impl Compiler {
+    fn label(&mut self) -> usize {
+        self.labels.push(usize::MAX);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = self.code.len();
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Num(n) => self.code.push(Code::Push(*n)),
+            Expr::Reg(r) => self.code.push(Code::Load(*r)),
+            Expr::Neg(e) => {
+                self.expr(e);
+                self.code.push(Code::Neg);
+            },
+            Expr::Bin(op, a, b) => {
+                self.expr(a);
+                self.expr(b);
+                self.code.push(Code::Arith(*op));
+            },
+        }
+    }
+
+    // Jumps to `target` when `cond` comes out as `when`, and falls
+    // through otherwise, so and/or/not short-circuit without ever
+    // putting a boolean on the stack.
+    fn branch(&mut self, cond: &Cond, when: bool, target: usize) {
+        match cond {
+            Cond::Cmp(a, op, b) => {
+                self.expr(a);
+                self.expr(b);
+                let op = if when { op.negate() } else { *op };
+                self.code.push(Code::JumpUnless(op, target));
+            },
+            Cond::Not(c) => self.branch(c, !when, target),
+            Cond::And(a, b) | Cond::Or(a, b) => {
+                // Whether the left side alone can decide it
+                let decides = matches!(cond, Cond::Or(_, _));
+                if decides == when {
+                    self.branch(a, when, target);
+                    self.branch(b, when, target);
+                } else {
+                    let skip = self.label();
+                    self.branch(a, decides, skip);
+                    self.branch(b, when, target);
+                    self.place(skip);
+                }
+            },
+        }
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Update(r, amount) => {
+                self.expr(amount);
+                self.code.push(Code::Add(*r));
+            },
+            Stmt::Block(body) => {
+                for s in body {
+                    self.statement(s);
+                }
+            },
+            Stmt::If(cond, then, otherwise) => {
+                let skip = self.label();
+                self.branch(cond, false, skip);
+                self.statement(then);
+                match otherwise {
+                    None => self.place(skip),
+                    Some(s) => {
+                        let end = self.label();
+                        self.code.push(Code::Jump(end));
+                        self.place(skip);
+                        self.statement(s);
+                        self.place(end);
+                    },
+                }
+            },
+        }
+    }
+}
+
+fn compile(statements: &[Stmt], names: Vec<String>) -> Program {
+    let mut c = Compiler { code: Vec::new(), labels: Vec::new() };
+    for s in statements {
+        c.statement(s);
+        c.code.push(Code::Step);
+    }
+    let code = c.code.iter()
+        .map(|code| match *code {
+            Code::JumpUnless(op, l) => Code::JumpUnless(op, c.labels[l]),
+            Code::Jump(l) => Code::Jump(c.labels[l]),
+            code => code,
+        })
+        .collect();
+    Program { code, names }
+}
+
+// Every register write, in order, and how many of them had happened
+// by the end of each top-level statement
+struct History {
+    writes: Vec<(usize, i64)>,
+    steps: Vec<usize>,
+}
+
+pub struct VM {
+    registers: Vec<i64>,
+    // Which registers an update has written to
+    written: Vec<bool>,
+    max_reg_value: i64,
+    history: Option<History>,
+}
+impl VM {
+    pub fn new(program: &Program) -> Self {
+        let n = program.names.len();
+        Self { registers: vec![0; n], written: vec![false; n], max_reg_value: 0, history: None }
+    }
+    // A VM that keeps every write, for registers_at
+    pub fn recording(program: &Program) -> Self {
+        Self { history: Some(History { writes: Vec::new(), steps: Vec::new() }), ..Self::new(program) }
+    }
+    // A division by zero or an overflow abandons the rest of the
+    // top-level statement it happens in. Updates that statement already
+    // made stand.
+    pub fn run(&mut self, program: &Program) {
+        let mut stack: Vec<i64> = Vec::new();
+        let mut pc = 0;
+        while pc < program.code.len() {
+            match program.code[pc] {
+                Code::Push(n) => stack.push(n),
+                Code::Load(r) => stack.push(self.registers[r]),
+                Code::Neg => {
+                    let a = stack.pop().unwrap();
+                    match a.checked_neg() {
+                        Some(v) => stack.push(v),
+                        None => {
+                            pc = abandon(program, pc, &mut stack);
+                            continue;
+                        },
+                    }
+                },
+                Code::Arith(op) => {
+                    let b = stack.pop().unwrap();
+                    let a = stack.pop().unwrap();
+                    match arith(op, a, b) {
+                        Some(v) => stack.push(v),
+                        None => {
+                            pc = abandon(program, pc, &mut stack);
+                            continue;
+                        },
+                    }
+                },
+                Code::JumpUnless(op, target) => {
+                    let value = stack.pop().unwrap();
+                    let cr_val = stack.pop().unwrap();
+                    if !compare(op, cr_val, value) {
+                        pc = target;
+                        continue;
+                    }
+                },
+                Code::Jump(target) => {
+                    pc = target;
+                    continue;
+                },
+                Code::Add(r) => {
+                    let incr = stack.pop().unwrap();
+                    let reg = &mut self.registers[r];
+                    let Some(v) = reg.checked_add(incr) else {
+                        pc = abandon(program, pc, &mut stack);
+                        continue;
+                    };
+                    *reg = v;
+                    self.written[r] = true;
+                    self.max_reg_value = self.max_reg_value.max(*reg);
+                    if let Some(h) = &mut self.history {
+                        h.writes.push((r, *reg));
+                    }
+                },
+                Code::Step => {
+                    if let Some(h) = &mut self.history {
+                        h.steps.push(h.writes.len());
+                    }
+                },
+            }
+            pc += 1;
+        }
+    }
+    // The registers, by slot, as they were after the first `step`
+    // top-level statements ran. Only a recording VM knows; None past
+    // the end of the run.
+    pub fn registers_at(&self, step: usize) -> Option<Vec<i64>> {
+        let h = self.history.as_ref()?;
+        let writes = if step == 0 { 0 } else { *h.steps.get(step - 1)? };
+        let mut registers = vec![0; self.registers.len()];
+        for &(r, v) in &h.writes[..writes] {
+            registers[r] = v;
+        }
+        Some(registers)
+    }
+}
+
+// The Step that ends the statement at `pc`, with the stack emptied
+fn abandon(program: &Program, pc: usize, stack: &mut Vec<i64>) -> usize {
+    stack.clear();
+    pc + program.code[pc..].iter().position(|c| *c == Code::Step).unwrap()
+}
+
+// None on division by zero or overflow
+fn arith(op: Arith, a: i64, b: i64) -> Option<i64> {
+    match op {
+        Arith::Add => a.checked_add(b),
+        Arith::Sub => a.checked_sub(b),
+        Arith::Mul => a.checked_mul(b),
+        Arith::Div => a.checked_div(b),
+        Arith::Mod => a.checked_rem(b),
+    }
+}
+
+fn compare(op: Op, cr_val: i64, value: i64) -> bool {
+    match op {
+        Op::Gt => cr_val > value,
+        Op::Gte => cr_val >= value,
+        Op::Lt => cr_val < value,
+        Op::Lte => cr_val <= value,
+        Op::Eq => cr_val == value,
+        Op::Neq => cr_val != value,
+    }
+}
+
+fn part1(input: &Program) -> i64 {
+    let mut vm = VM::new(input);
+    vm.run(input);
+    // Registers that only ever appear in conditions don't count
+    vm.registers.iter()
+        .zip(&vm.written)
+        .filter_map(|(v, w)| w.then_some(*v))
+        .max()
+        .unwrap_or(0)
+}
+fn part2(input: &Program) -> i64 {
+    let mut vm = VM::new(input);
+    vm.run(input);
+    vm.max_reg_value
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Program;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_program(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day08_test() {
+        let input = Day08::parse(&test_input::<String>(include_str!("day08.testinput"))).unwrap();
+        assert_eq!(input.names, vec!["b", "a", "c"]);
+        assert_eq!(part1(&input), 1);
+        assert_eq!(part2(&input), 10);
+        let mut vm = VM::recording(&input);
+        vm.run(&input);
+        assert_eq!(vm.registers_at(0), Some(vec![0, 0, 0]));
+        assert_eq!(vm.registers_at(2), Some(vec![0, 1, 0]));
+        assert_eq!(vm.registers_at(3).unwrap().into_iter().max(), Some(10));
+        assert_eq!(vm.registers_at(4), Some(vm.registers.clone()));
+        assert_eq!(vm.registers_at(5), None);
+
+        let src = "\
+x inc 7
+y inc x * 2 - 1 if (x + 1) % 4 == 0 and not y > 0
+{
+    z inc 100 if (x > 5 or y < 0) and z == 0 else z dec 1
+    x dec -x / 2
+}
+w inc 3 if x < 0 else { w inc 1  w inc y }";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let input = Day08::parse(&lines).unwrap();
+        let mut vm = VM::new(&input);
+        vm.run(&input);
+        assert_eq!(input.names, vec!["x", "y", "z", "w"]);
+        assert_eq!(vm.registers, vec![10, 13, 100, 14]);
+        assert_eq!(part2(&input), 100);
+
+        let input = Day08::parse(&["a dec 5 if b == 0".to_string()]).unwrap();
+        assert_eq!(part1(&input), -5);
+
+        let src = "a inc 1 / b\nc inc 2\n{ c inc 3  d inc 1 % b  c inc 4 }\ne dec -9223372036854775807 - 1";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let input = Day08::parse(&lines).unwrap();
+        let mut vm = VM::new(&input);
+        vm.run(&input);
+        assert_eq!(vm.registers, vec![0, 0, 5, 0, 0]);
+        assert_eq!(part1(&input), 5);
+
+        let err = |src: &str| {
+            let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+            Day08::parse(&lines).err().unwrap().to_string()
+        };
+        assert_eq!(err("a inc 1 if (b > 1 and ) or c"), "line 1, column 23: expected expression, found \")\"");
+        assert_eq!(err("a inc 1\n{ b dec 2"), "line 2, column 10: expected '}', found end of line");
+        assert_eq!(err("a add 1 if b > 1"), "line 1, column 3: expected inc or dec, found \"add\"");
+        assert_eq!(err("a inc 1 if b # 1"), "line 1, column 14: expected expression, found \"#\"");
+        assert_eq!(err("if inc 1"), "line 1, column 1: expected register or '{', found \"if\"");
+
+        let nest = |open: &str, n: usize, close: &str| format!("a inc {}1{}", open.repeat(n), close.repeat(n));
+        assert!(Day08::parse(&[nest("(", 256, ")")]).is_ok());
+        let deep = format!("a inc 1 if {}b > 1{}", "not (".repeat(120), ")".repeat(120));
+        assert!(Day08::parse(&[deep]).is_ok());
+        assert_eq!(err(&nest("(", 100_000, ")")), "line 1, column 264: expected at most 256 levels of nesting, found \"(\"");
+        assert!(err(&nest("-", 100_000, "")).contains("expected at most 256 levels of nesting"));
+        assert!(err(&nest("1 + ", 100_000, "")).contains("expected at most 256 levels of nesting"));
+        assert!(err(&format!("{}a inc 1{}", "{ ".repeat(100_000), " }".repeat(100_000))).contains("256 levels"));
+        assert!(err(&format!("a inc 1 if {}b > 1", "not ".repeat(100_000))).contains("256 levels"));
+    }
+}
@@ -0,0 +1,772 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::str::FromStr;
+use crate::parse::{first_line, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+// What the stream parser reports, along with the byte offset where each
+// one starts
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event<'a> {
+    StartGroup,
+    EndGroup,
+    StartGarbage,
+    // A run of plain characters inside garbage. Long garbage may come
+    // in several chunks.
+    Garbage(&'a [u8]),
+    // A `!` and the character it cancels
+    Cancel(&'a [u8]),
+    EndGarbage,
+    // The `,` between two items
+    Separator,
+    // Whitespace after the outermost group
+    Trailing(&'a [u8]),
+}
+
+impl<'a> Event<'a> {
+    // The bytes of the stream this event stands for
+    pub fn bytes(&self) -> &'a [u8] {
+        match *self {
+            Event::StartGroup => b"{",
+            Event::EndGroup => b"}",
+            Event::StartGarbage => b"<",
+            Event::Garbage(b) | Event::Cancel(b) | Event::Trailing(b) => b,
+            Event::EndGarbage => b">",
+            Event::Separator => b",",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Malformed { offset: usize, found: Option<u8>, expected: &'static str },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{e}"),
+            StreamError::Malformed { offset, found, expected } => {
+                write!(f, "byte {offset}: expected {expected}, found ")?;
+                match found {
+                    Some(b) if b.is_ascii_graphic() || *b == b' ' => write!(f, "\"{}\"", *b as char),
+                    Some(b) => write!(f, "byte 0x{b:02x}"),
+                    None => write!(f, "end of input"),
+                }
+            },
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    // Before the outermost group
+    Start,
+    // Just inside a `{`
+    Open,
+    // After an item, waiting for `,` or `}`
+    Next,
+    // After a `,`
+    Item,
+    Garbage,
+    // After a `!`, with the bytes of the canceled character still to come
+    Cancel(usize),
+    // After the outermost group, where only whitespace may follow
+    Done,
+}
+
+// Parses the stream one buffer at a time, keeping only the nesting depth
+// and the state it stopped in, so input of any size and nesting can be
+// fed to it in pieces of any size.
+pub struct StreamParser {
+    state: State,
+    depth: usize,
+    // Bytes consumed so far
+    offset: usize,
+    // The `!` and canceled character being collected
+    cancel: [u8; 5],
+    canceled: usize,
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Bytes in the UTF-8 character starting with `b`
+fn char_len(b: u8) -> usize {
+    match b.leading_ones() {
+        2 => 2,
+        3 => 3,
+        4 => 4,
+        _ => 1,
+    }
+}
+
+impl StreamParser {
+    pub fn new() -> Self {
+        Self { state: State::Start, depth: 0, offset: 0, cancel: [0; 5], canceled: 0 }
+    }
+
+    fn malformed(&self, at: usize, found: Option<u8>) -> StreamError {
+        let expected = match self.state {
+            State::Start => "'{'",
+            State::Open => "'{', '<' or '}'",
+            State::Next => "',' or '}'",
+            State::Item => "'{' or '<'",
+            State::Garbage => "'>'",
+            State::Cancel(_) => "canceled character",
+            State::Done => "end of input",
+        };
+        StreamError::Malformed { offset: at, found, expected }
+    }
+
+    pub fn feed<F>(&mut self, bytes: &[u8], handler: &mut F) -> Result<(), StreamError>
+    where F: FnMut(usize, Event) {
+        let mut i = 0;
+        while i < bytes.len() {
+            let at = self.offset + i;
+            let b = bytes[i];
+            match (self.state, b) {
+                (State::Garbage, b'!') => {
+                    self.cancel[0] = b;
+                    self.canceled = 1;
+                    self.state = State::Cancel(0);
+                },
+                (State::Garbage, b'>') => {
+                    handler(at, Event::EndGarbage);
+                    self.state = State::Next;
+                },
+                (State::Garbage, _) => {
+                    let len = bytes[i..].iter().position(|b| *b == b'!' || *b == b'>').unwrap_or(bytes.len() - i);
+                    handler(at, Event::Garbage(&bytes[i..i + len]));
+                    i += len;
+                    continue;
+                },
+                (State::Cancel(left), _) => {
+                    self.cancel[self.canceled] = b;
+                    self.canceled += 1;
+                    let left = if self.canceled == 2 { char_len(b) - 1 } else { left - 1 };
+                    if left == 0 {
+                        let start = at + 1 - self.canceled;
+                        handler(start, Event::Cancel(&self.cancel[..self.canceled]));
+                        self.state = State::Garbage;
+                    } else {
+                        self.state = State::Cancel(left);
+                    }
+                },
+                (State::Start | State::Open | State::Item, b'{') => {
+                    handler(at, Event::StartGroup);
+                    self.depth += 1;
+                    self.state = State::Open;
+                },
+                (State::Open | State::Item, b'<') => {
+                    handler(at, Event::StartGarbage);
+                    self.state = State::Garbage;
+                },
+                (State::Open | State::Next, b'}') => {
+                    handler(at, Event::EndGroup);
+                    self.depth -= 1;
+                    self.state = if self.depth == 0 { State::Done } else { State::Next };
+                },
+                (State::Next, b',') => {
+                    handler(at, Event::Separator);
+                    self.state = State::Item;
+                },
+                (State::Done, _) if b.is_ascii_whitespace() => {
+                    let len = bytes[i..].iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len() - i);
+                    handler(at, Event::Trailing(&bytes[i..i + len]));
+                    i += len;
+                    continue;
+                },
+                _ => return Err(self.malformed(at, Some(b))),
+            }
+            i += 1;
+        }
+        self.offset += bytes.len();
+        Ok(())
+    }
+
+    // Checks the input didn't stop part way through
+    pub fn finish(&self) -> Result<(), StreamError> {
+        match self.state {
+            State::Done => Ok(()),
+            _ => Err(self.malformed(self.offset, None)),
+        }
+    }
+}
+
+// Parses everything `reader` produces, passing each event to `handler`.
+pub fn parse_stream<R, F>(mut reader: R, mut handler: F) -> Result<(), StreamError>
+where R: Read, F: FnMut(usize, Event) {
+    let mut parser = StreamParser::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return parser.finish(),
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        parser.feed(&buf[..n], &mut handler)?;
+    }
+}
+
+// Totals worked out as the events go by
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub groups: usize,
+    // Each group scores its depth, the outermost one being 1
+    pub score: i64,
+    // Garbage characters, not counting the delimiters or anything canceled
+    pub garbage: i64,
+    pub cancels: usize,
+}
+
+impl Stats {
+    pub fn read<R: Read>(reader: R) -> Result<Self, StreamError> {
+        let mut stats = Stats::default();
+        let mut depth = 0;
+        parse_stream(reader, |_, event| match event {
+            Event::StartGroup => {
+                depth += 1;
+                stats.groups += 1;
+                stats.score += depth;
+            },
+            Event::EndGroup => depth -= 1,
+            Event::Garbage(chunk) => stats.garbage += chunk.len() as i64,
+            Event::Cancel(_) => stats.cancels += 1,
+            Event::StartGarbage | Event::EndGarbage | Event::Separator | Event::Trailing(_) => {},
+        })?;
+        Ok(stats)
+    }
+}
+
+// The stream as a tree that keeps every byte, so it can be written back
+// out exactly as it was read. Groups can nest far deeper than the stack
+// could follow, so Clone, PartialEq, Debug and Drop are all written out
+// with explicit stacks rather than derived.
+#[derive(Default, Eq)]
+pub struct Group {
+    pub children: Vec<GG>,
+}
+
+impl Clone for Group {
+    fn clone(&self) -> Self {
+        // Each group being copied, how many of its children are done,
+        // and the copies made so far
+        let mut stack: Vec<(&Group, usize, Vec<GG>)> = vec![(self, 0, Vec::new())];
+        loop {
+            let top = stack.last_mut().unwrap();
+            let (group, i) = (top.0, top.1);
+            top.1 += 1;
+            match group.children.get(i) {
+                Some(GG::Group(g)) => stack.push((g, 0, Vec::new())),
+                Some(GG::Garbage(g)) => top.2.push(GG::Garbage(g.clone())),
+                None => {
+                    let (_, _, children) = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some(parent) => parent.2.push(GG::Group(Group { children })),
+                        None => return Group { children },
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl PartialEq for Group {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((a, b)) = stack.pop() {
+            if a.children.len() != b.children.len() {
+                return false;
+            }
+            for pair in a.children.iter().zip(&b.children) {
+                match pair {
+                    (GG::Group(x), GG::Group(y)) => stack.push((x, y)),
+                    (GG::Garbage(x), GG::Garbage(y)) if x == y => {},
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+}
+
+// Shown as the stream it serializes to
+impl fmt::Debug for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = Vec::new();
+        self.events(|event| out.extend_from_slice(event.bytes()));
+        f.debug_tuple("Group").field(&String::from_utf8_lossy(&out)).finish()
+    }
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        let mut stack = std::mem::take(&mut self.children);
+        while let Some(child) = stack.pop() {
+            if let GG::Group(mut g) = child {
+                stack.append(&mut g.children);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GG {
+    Group(Group),
+    Garbage(Garbage),
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Garbage {
+    pub pieces: Vec<Piece>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Piece {
+    Text(Vec<u8>),
+    // The `!` along with the character it cancels
+    Cancel(Vec<u8>),
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Tree {
+    pub root: Group,
+    pub trailing: Vec<u8>,
+}
+
+// The garbage being read, which is always the last child of the
+// innermost open group
+fn last_garbage(open: &mut [Group]) -> &mut Vec<Piece> {
+    match open.last_mut().and_then(|g| g.children.last_mut()) {
+        Some(GG::Garbage(g)) => &mut g.pieces,
+        _ => unreachable!("garbage event outside garbage"),
+    }
+}
+
+impl Tree {
+    // Builds the tree from the stream parser's events, keeping the
+    // groups still open on a stack rather than recursing.
+    pub fn read<R: Read>(reader: R) -> Result<Self, StreamError> {
+        let mut tree = Tree::default();
+        let mut open: Vec<Group> = Vec::new();
+        parse_stream(reader, |_, event| {
+            match event {
+                Event::StartGroup => open.push(Group::default()),
+                Event::EndGroup => {
+                    let group = open.pop().unwrap();
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(GG::Group(group)),
+                        None => tree.root = group,
+                    }
+                },
+                Event::StartGarbage => open.last_mut().unwrap().children.push(GG::Garbage(Garbage::default())),
+                Event::Garbage(text) => {
+                    let pieces = last_garbage(&mut open);
+                    match pieces.last_mut() {
+                        Some(Piece::Text(t)) => t.extend_from_slice(text),
+                        _ => pieces.push(Piece::Text(text.to_vec())),
+                    }
+                },
+                Event::Cancel(c) => last_garbage(&mut open).push(Piece::Cancel(c.to_vec())),
+                Event::Trailing(t) => tree.trailing.extend_from_slice(t),
+                Event::EndGarbage | Event::Separator => {},
+            }
+        })?;
+        Ok(tree)
+    }
+
+    // Feeds `handler` the same events, with the same offsets, that
+    // parsing the serialized tree would.
+    pub fn replay<F>(&self, mut handler: F)
+    where F: FnMut(usize, Event) {
+        let mut at = 0;
+        let mut emit = |event: Event| {
+            handler(at, event);
+            at += event.bytes().len();
+        };
+        self.root.events(&mut emit);
+        if !self.trailing.is_empty() {
+            emit(Event::Trailing(&self.trailing));
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.replay(|_, event| out.extend_from_slice(event.bytes()));
+        out
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut result = Ok(());
+        self.replay(|_, event| {
+            if result.is_ok() {
+                result = writer.write_all(event.bytes());
+            }
+        });
+        result
+    }
+
+    // One line per group and per garbage, indented by depth, with each
+    // group's depth and the score of everything inside it:
+    //
+    //   { (depth 1, score 6)
+    //     { (depth 2, score 5)
+    //       {} (depth 3, score 3)
+    //     }
+    //     <a!>b>
+    //   }
+    pub fn pretty(&self) -> String {
+        // Scores by group, in the order the groups open
+        let mut scores = Vec::new();
+        let mut open: Vec<(usize, i64)> = Vec::new();
+        self.replay(|_, event| match event {
+            Event::StartGroup => {
+                open.push((scores.len(), open.len() as i64 + 1));
+                scores.push(0);
+            },
+            Event::EndGroup => {
+                let (idx, score) = open.pop().unwrap();
+                scores[idx] = score;
+                if let Some(parent) = open.last_mut() {
+                    parent.1 += score;
+                }
+            },
+            _ => {},
+        });
+
+        let mut out = String::new();
+        let mut depth = 0;
+        let mut groups = 0;
+        // A group that has opened but might still turn out to be empty
+        let mut pending: Option<String> = None;
+        let mut garbage = Vec::new();
+        self.replay(|_, event| {
+            // A group's own line sits one level out from its contents
+            let indent = "  ".repeat(depth);
+            let mut flush = |out: &mut String| {
+                if let Some(p) = pending.take() {
+                    out.push_str(&format!("{}{{ {p}\n", &indent[2..]));
+                }
+            };
+            match event {
+                Event::StartGroup => {
+                    flush(&mut out);
+                    depth += 1;
+                    pending = Some(format!("(depth {depth}, score {})", scores[groups]));
+                    groups += 1;
+                },
+                Event::EndGroup => {
+                    depth -= 1;
+                    let indent = "  ".repeat(depth);
+                    match pending.take() {
+                        Some(p) => out.push_str(&format!("{indent}{{}} {p}\n")),
+                        None => out.push_str(&format!("{indent}}}\n")),
+                    }
+                },
+                Event::StartGarbage => {
+                    flush(&mut out);
+                    garbage.clear();
+                    garbage.push(b'<');
+                },
+                Event::Garbage(b) | Event::Cancel(b) => garbage.extend_from_slice(b),
+                Event::EndGarbage => {
+                    out.push_str(&format!("{indent}{}>\n", String::from_utf8_lossy(&garbage)));
+                },
+                Event::Separator | Event::Trailing(_) => {},
+            }
+        });
+        out
+    }
+}
+
+// Where an item sits: the index among its parent's children at each
+// level down from the group it's looked up in, written like `0/2/1`.
+// The empty path is the group itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Path(pub Vec<usize>);
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", parts.join("/"))
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Path::default());
+        }
+        s.split('/')
+            .map(|t| parse_token(s, t, "child index"))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Path)
+    }
+}
+
+impl Garbage {
+    // Characters that count towards the puzzle's total
+    pub fn characters(&self) -> usize {
+        self.pieces.iter()
+            .map(|p| match p {
+                Piece::Text(t) => t.len(),
+                Piece::Cancel(_) => 0,
+            })
+            .sum()
+    }
+}
+
+// Queries work on any group, so they can be asked of a subtree as
+// well as the whole stream. None of them recurse.
+impl Group {
+    // The events for this group's own text, braces included
+    fn events<'a, F>(&'a self, mut emit: F)
+    where F: FnMut(Event<'a>) {
+        emit(Event::StartGroup);
+        // Each open group and how many of its children are done
+        let mut stack: Vec<(&Group, usize)> = vec![(self, 0)];
+        while let Some(&(group, i)) = stack.last() {
+            let Some(child) = group.children.get(i) else {
+                emit(Event::EndGroup);
+                stack.pop();
+                continue;
+            };
+            stack.last_mut().unwrap().1 += 1;
+            if i > 0 {
+                emit(Event::Separator);
+            }
+            match child {
+                GG::Group(g) => {
+                    emit(Event::StartGroup);
+                    stack.push((g, 0));
+                },
+                GG::Garbage(g) => {
+                    emit(Event::StartGarbage);
+                    for piece in &g.pieces {
+                        emit(match piece {
+                            Piece::Text(t) => Event::Garbage(t),
+                            Piece::Cancel(c) => Event::Cancel(c),
+                        });
+                    }
+                    emit(Event::EndGarbage);
+                },
+            }
+        }
+    }
+
+    // Calls `visit` with every item below the group, parents first,
+    // along with its path and the depth of the group holding it (1 for
+    // this group's own children).
+    fn walk<'a, F>(&'a self, mut visit: F)
+    where F: FnMut(&[usize], usize, &'a GG) {
+        let mut path = Vec::new();
+        let mut stack: Vec<(&Group, usize)> = vec![(self, 0)];
+        while let Some(&(group, i)) = stack.last() {
+            let Some(child) = group.children.get(i) else {
+                stack.pop();
+                path.pop();
+                continue;
+            };
+            stack.last_mut().unwrap().1 += 1;
+            path.push(i);
+            visit(&path, stack.len(), child);
+            match child {
+                GG::Group(g) => stack.push((g, 0)),
+                GG::Garbage(_) => { path.pop(); },
+            }
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&GG> {
+        let (last, parents) = path.0.split_last()?;
+        self.subtree(&Path(parents.to_vec()))?.children.get(*last)
+    }
+
+    pub fn subtree(&self, path: &Path) -> Option<&Group> {
+        let mut group = self;
+        for i in &path.0 {
+            group = match group.children.get(*i)? {
+                GG::Group(g) => g,
+                GG::Garbage(_) => return None,
+            };
+        }
+        Some(group)
+    }
+
+    // With this group at depth 1
+    pub fn max_depth(&self) -> usize {
+        self.depth_histogram().keys().last().copied().unwrap_or(1)
+    }
+
+    // How many groups there are at each depth
+    pub fn depth_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::from([(1, 1)]);
+        self.walk(|_, depth, item| {
+            if let GG::Group(_) = item {
+                *histogram.entry(depth + 1).or_insert(0) += 1;
+            }
+        });
+        histogram
+    }
+
+    // The garbage with the most characters, the first one on a tie
+    pub fn largest_garbage(&self) -> Option<(Path, &Garbage)> {
+        let mut largest: Option<(Path, &Garbage)> = None;
+        self.walk(|path, _, item| {
+            if let GG::Garbage(g) = item {
+                if largest.as_ref().is_none_or(|(_, l)| g.characters() > l.characters()) {
+                    largest = Some((Path(path.to_vec()), g));
+                }
+            }
+        });
+        largest
+    }
+
+    pub fn cancels(&self) -> usize {
+        let mut cancels = 0;
+        self.walk(|_, _, item| {
+            if let GG::Garbage(g) = item {
+                cancels += g.pieces.iter().filter(|p| matches!(p, Piece::Cancel(_))).count();
+            }
+        });
+        cancels
+    }
+}
+
+fn part1(input: &Stats) -> i64 {
+    input.score
+}
+
+fn part2(input: &Stats) -> i64 {
+    input.garbage
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Stats;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        let line = first_line(input)?;
+        Stats::read(line.as_bytes()).map_err(|e| match e {
+            StreamError::Malformed { offset, found: Some(_), expected } => {
+                let end = (offset + 1..=line.len()).find(|i| line.is_char_boundary(*i)).unwrap_or(line.len());
+                ParseError::new(line, &line[offset..end], expected).at_line(1)
+            },
+            StreamError::Malformed { expected, .. } => ParseError::eol(line, expected).at_line(1),
+            StreamError::Io(e) => ParseError::eol(line, &e.to_string()).at_line(1),
+        })
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day09_test() {
+        let stats = |s: &str| Stats::read(s.as_bytes()).unwrap();
+        assert_eq!(stats("{}").score, 1);
+        assert_eq!(stats("{{{}}}").score, 6);
+        assert_eq!(stats("{{},{}}").score, 5);
+        assert_eq!(stats("{{<a!>},{<a!>},{<a!>},{<ab>}}\n").score, 3);
+        assert_eq!(stats("{<{o\"i!a,<{i<a>}").garbage, 10);
+        assert_eq!(stats("{<!!!>>,<é!é>}"), Stats { groups: 1, score: 1, garbage: 2, cancels: 3 });
+
+        // Fed one byte at a time, only the garbage chunks change
+        let src = b"{{<!>},{<a!>b>},{<a>}}";
+        let mut whole = Vec::new();
+        parse_stream(&src[..], |at, e| whole.push((at, format!("{e:?}")))).unwrap();
+        let mut parser = StreamParser::new();
+        let mut events = Vec::new();
+        for b in src.chunks(1) {
+            parser.feed(b, &mut |at, e| events.push((at, format!("{e:?}")))).unwrap();
+        }
+        parser.finish().unwrap();
+        assert_eq!(whole[..5], [
+            (0, "StartGroup".to_string()),
+            (1, "StartGroup".to_string()),
+            (2, "StartGarbage".to_string()),
+            (3, "Cancel([33, 62])".to_string()),
+            (5, "Garbage([125, 44, 123, 60, 97])".to_string()),
+        ]);
+        assert_eq!(events[4..9].iter().map(|(at, _)| *at).collect::<Vec<_>>(), vec![5, 6, 7, 8, 9]);
+        assert_eq!(events.len(), whole.len() + 4);
+
+        let src = "{{<!>},{<a!>b>},{{<é!é>,<>},{}}}\n";
+        let tree = Tree::read(src.as_bytes()).unwrap();
+        assert_eq!(tree.to_bytes(), src.as_bytes());
+        let mut replayed = Vec::new();
+        tree.replay(|at, e| replayed.push((at, format!("{e:?}"))));
+        let mut parsed = Vec::new();
+        parse_stream(src.as_bytes(), |at, e| parsed.push((at, format!("{e:?}")))).unwrap();
+        assert_eq!(replayed, parsed);
+        assert_eq!(tree.pretty(), "\
+{ (depth 1, score 11)
+  { (depth 2, score 2)
+    <!>},{<a!>b>
+  }
+  { (depth 2, score 8)
+    { (depth 3, score 3)
+      <é!é>
+      <>
+    }
+    {} (depth 3, score 3)
+  }
+}
+");
+
+        let root = &tree.root;
+        assert_eq!(root.max_depth(), 3);
+        assert_eq!(root.depth_histogram(), BTreeMap::from([(1, 1), (2, 2), (3, 2)]));
+        let (path, garbage) = root.largest_garbage().unwrap();
+        assert_eq!((path.to_string(), garbage.characters()), ("0/0".to_string(), 6));
+        assert_eq!(root.cancels(), 3);
+        let path = "1/0".parse::<Path>().unwrap();
+        assert_eq!(root.get(&"1/0/1".parse().unwrap()), Some(&GG::Garbage(Garbage::default())));
+        assert_eq!(root.subtree(&path).unwrap().cancels(), 1);
+        assert_eq!(root.subtree(&path).unwrap().max_depth(), 1);
+        assert_eq!(format!("{:?}", root.subtree(&path).unwrap()), "Group(\"{<é!é>,<>}\")");
+        assert_eq!(root.subtree(&"0/0".parse().unwrap()), None);
+        assert_eq!(root.subtree(&Path::default()), Some(root));
+        assert_eq!("1/x".parse::<Path>().unwrap_err().to_string(), "column 3: expected child index, found \"x\"");
+
+        // Far deeper than the stack could take one frame per level
+        let deep = "{".repeat(1_000_000) + &"}".repeat(1_000_000);
+        let tree = Tree::read(deep.as_bytes()).unwrap();
+        assert_eq!(tree.root.max_depth(), 1_000_000);
+        let copy = tree.clone();
+        assert_eq!(copy, tree);
+        assert_eq!(format!("{:?}", copy.root).len(), deep.len() + "Group(\"\")".len());
+        drop(tree);
+
+        let err = |s: &str| Stats::read(s.as_bytes()).unwrap_err().to_string();
+        assert_eq!(err("{{},x}"), "byte 4: expected '{' or '<', found \"x\"");
+        assert_eq!(err("{{<a!>}"), "byte 7: expected '>', found end of input");
+        assert_eq!(err("{}}"), "byte 2: expected end of input, found \"}\"");
+        assert_eq!(Day09::parse(&["{<>é".to_string()]).err().unwrap().to_string(),
+            "line 1, column 4: expected ',' or '}', found \"é\"");
+    }
+}
@@ -0,0 +1,58 @@
+use std::vec::Vec;
+use crate::{knot_hash_raw, KnotHasher};
+use crate::parse::{first_line, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+fn part1(lengths: &[usize]) -> usize {
+    let mut list = Vec::from_iter(0u8..=255);
+    knot_hash_raw(&mut list, lengths.iter().copied(), 1);
+    list[0] as usize * list[1] as usize
+}
+
+fn part2(input: &str) -> String {
+    let mut hasher = KnotHasher::new();
+    hasher.update(input.as_bytes());
+    hasher.finalize().to_string()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    // Part 1 reads the line as lengths, part 2 hashes it as it is
+    type Input = (Vec<usize>, String);
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        let line = first_line(input)?;
+        let lengths = line.split(',')
+            .map(|n| parse_token(line, n, "length"))
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|e| e.at_line(1))?;
+        Ok((lengths, line.to_string()))
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(&input.0).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(&input.1).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day10_test() {
+        let mut list = Vec::from_iter(0u8..5);
+        let input = vec![3,4,1,5];
+        knot_hash_raw(&mut list, input.iter().copied(), 1);
+        assert_eq!(list[0] * list[1], 12);
+        assert_eq!(Day10::parse(&["3,4,1,5".to_string()]).unwrap().0, input);
+        assert_eq!(Day10::parse(&["1,x,3".to_string()]).err().unwrap().to_string(),
+            "line 1, column 3: expected length, found \"x\"");
+
+        assert_eq!(part2(""), "a2582a3a0e66e6e86e3812dcb672a272");
+        assert_eq!(part2("AoC 2017"), "33efeb34ea91902bb2f59c9920caa6cd");
+        assert_eq!(part2("1,2,3"), "3efbe78a8d82f29979031a4aa0b16a9d");
+        assert_eq!(part2("1,2,4"), "63960835bcdc130f0b66d7ff4f6a5a8e");
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::parse::{next_token, parse_lines, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Input {
+    program: usize,
+    neighbors: Vec<usize>,
+}
+impl FromStr for Input {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut itr = s.split(" <-> ");
+        let program = parse_token(s, next_token(s, &mut itr, "program id")?, "program id")?;
+        let itr = next_token(s, &mut itr, "\" <-> \"")?.split(", ");
+        let neighbors: Vec<usize> = itr
+            .map(|ss| parse_token(s, ss, "program id"))
+            .collect::<Result<_, _>>()?;
+        Ok(Input{program, neighbors})
+    }
+}
+
+fn get_neighbors(node: usize, map: &HashMap<usize, Vec<usize>>, traversed: &mut HashSet<usize>) {
+    if traversed.contains(&node) { return; }
+    traversed.insert(node);
+    for n in map[&node].iter() {
+        get_neighbors(*n, map, traversed);
+    }
+}
+
+fn part1(input: &[Input]) -> usize {
+    let map: HashMap<usize, Vec<usize>> = HashMap::from_iter(
+        input.iter().map(|row| (row.program, row.neighbors.clone()))
+    );
+    let mut neighbors = HashSet::new();
+    get_neighbors(0, &map, &mut neighbors);
+    neighbors.len()
+}
+
+fn part2(input: &[Input]) -> usize {
+    let map: HashMap<usize, Vec<usize>> = HashMap::from_iter(
+        input.iter().map(|row| (row.program, row.neighbors.clone()))
+    );
+    let mut remaining: HashSet<usize> = HashSet::from_iter(map.keys().cloned());
+    let mut ngroups = 0;
+    while !remaining.is_empty() {
+        let p = *remaining.iter().next().unwrap();
+        let mut group = HashSet::new();
+        get_neighbors(p, &map, &mut group);
+        for n in group {
+            remaining.remove(&n);
+        }
+        ngroups += 1;
+    }
+    ngroups
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Input>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_lines(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day12_test() {
+        let input: Vec<Input> = test_input(include_str!("day12.testinput"));
+        assert_eq!(part1(&input), 6);
+        assert_eq!(part2(&input), 2);
+    }
+}
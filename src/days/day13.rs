@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+use std::iter::Iterator;
+use std::str::FromStr;
+use crate::parse::{next_token, parse_lines, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Input {
+    layer: usize,
+    range: usize,
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter = s.split(": ");
+        let layer = parse_token(s, next_token(s, &mut iter, "layer")?, "layer")?;
+        let range = parse_token(s, next_token(s, &mut iter, "\": \"")?, "range")?;
+        Ok(Input{layer, range})
+    }
+}
+
+struct Scanner {
+    range: usize,
+}
+impl Scanner {
+    fn new(range: usize) -> Self {
+        Self{range}
+    }
+    fn period(&self) -> usize {
+        self.range * 2 - 2
+    }
+    fn loc_at(&self, time: usize) -> usize {
+        if self.range == 2 { return time % 2; }
+        let loc = time % self.period();
+        if loc >= self.range {
+            self.range - (loc - self.range + 2)
+        } else {
+            loc
+        }
+    }
+}
+
+fn part1(input: &[Input]) -> usize {
+    let map: HashMap<usize, Scanner> = HashMap::from_iter(
+        input.iter().map(|i| (i.layer, Scanner::new(i.range)))
+    );
+    let maxdepth = map.keys().cloned().max().unwrap();
+    (0..=maxdepth)
+        .filter(|d| map.contains_key(d))
+        .filter(|d| map[d].loc_at(*d) == 0)
+        .map(|d| map[&d].range * d)
+        .sum()
+}
+
+fn part2(input: &[Input]) -> usize {
+    let map: HashMap<usize, Scanner> = HashMap::from_iter(
+        input.iter().map(|i| (i.layer, Scanner::new(i.range)))
+    );
+    let maxdepth = map.keys().cloned().max().unwrap();
+    for delay in 1.. {
+        if (0..=maxdepth)
+                .filter(|d| map.contains_key(d))
+                .all(|d| map[&d].loc_at(d + delay) != 0) {
+            return delay;
+        }
+    }
+    panic!()
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Input>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_lines(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day13_test() {
+        let s = Scanner::new(3);
+        let vals = [0, 1, 2, 1, 0, 1, 2];
+        vals.iter().enumerate().for_each(|(t, v)| assert_eq!(s.loc_at(t), *v));
+
+        let s = Scanner::new(4);
+        let vals = [0, 1, 2, 3, 2, 1, 0, 1];
+        vals.iter().enumerate().for_each(|(t, v)| assert_eq!(s.loc_at(t), *v));
+
+        let input: Vec<Input> = test_input("0: 3\n1: 2\n4: 4\n6: 4\n");
+        assert_eq!(part1(&input), 24);
+        assert_eq!(part2(&input), 10);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::parse::{next_token, parse_first, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+enum DanceMove {
+    Spin(usize),
+    Exchange(usize, usize),
+    Partner(char, char),
+}
+impl FromStr for DanceMove {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let c = s.chars().next().ok_or_else(|| ParseError::eol(s, "dance move"))?;
+        let ss = &s[c.len_utf8()..];
+        let program = |p: &str| {
+            let mut chars = p.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(ParseError::new(s, p, "program name")),
+            }
+        };
+        match c {
+            's' => Ok(DanceMove::Spin(parse_token(s, ss, "spin size")?)),
+            'x' => {
+                let mut i = ss.split('/');
+                let a = parse_token(s, next_token(s, &mut i, "position")?, "position")?;
+                let b = parse_token(s, next_token(s, &mut i, "'/'")?, "position")?;
+                Ok(DanceMove::Exchange(a, b))
+            },
+            'p' => {
+                let mut i = ss.split('/');
+                let a = program(next_token(s, &mut i, "program name")?)?;
+                let b = program(next_token(s, &mut i, "'/'")?)?;
+                Ok(DanceMove::Partner(a, b))
+            },
+            _ => Err(ParseError::new(s, &s[..c.len_utf8()], "s, x or p")),
+        }
+    }
+}
+
+pub struct DanceMoves(Vec<DanceMove>);
+
+impl FromStr for DanceMoves {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|ss| ss.parse::<DanceMove>().map_err(|e| e.within(s, ss)))
+            .collect::<Result<Vec<DanceMove>, _>>()
+            .map(Self)
+    }
+}
+
+fn dance(moves: &[DanceMove], progs: &mut [char]) -> Vec<char> {
+    let n_progs = progs.len();
+    let mut front: usize = 0;
+    for m in moves {
+        match m {
+            DanceMove::Spin(n) => {
+                front = (n_progs + front - n) % n_progs;
+            },
+            DanceMove::Exchange(i, j) => {
+                let ii = (front + i) % n_progs;
+                let jj = (front + j) % n_progs;
+                progs.swap(ii, jj);
+            },
+            DanceMove::Partner(a, b) => {
+                let i = progs.iter().position(|&x| x == *a).unwrap();
+                let j = progs.iter().position(|&x| x == *b).unwrap();
+                progs.swap(i, j);
+            },
+        }
+    }
+    (0..n_progs)
+        .map(|idx| (front + idx) % n_progs)
+        .map(|idx| progs[idx])
+        .collect()
+}
+
+fn dances(moves: &[DanceMove], n_progs: usize, n_dances: usize) -> String {
+    let mut progs: Vec<char> = Vec::from_iter('a'..(b'a' + n_progs as u8) as char);
+    let mut patterns: HashMap<Vec<char>, usize> = HashMap::new();
+    patterns.insert(progs.clone(), 0);
+    for i in 1..=n_dances {
+        progs = dance(moves, &mut progs);
+        if i == n_dances { break; }
+        if patterns.contains_key(&progs) {
+            let rpt_start = patterns[&progs];
+            let rpt_len = i - rpt_start;
+            let idx = (n_dances - rpt_start) % rpt_len + rpt_start;
+            progs = patterns.iter().find(|(_, &n)| n == idx).unwrap().0.clone();
+            break;
+        }
+        patterns.insert(progs.clone(), i);
+    }
+    progs.iter().collect()
+}
+
+fn part1(input: &[DanceMove]) -> String {
+    dances(input, 16, 1)
+}
+
+fn part2(input: &[DanceMove]) -> String {
+    dances(input, 16, 1_000_000_000)
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = DanceMoves;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_first(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(&input.0).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(&input.0).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day16_test() {
+        let input = "s1,x3/4,pe/b".parse::<DanceMoves>().unwrap();
+        assert_eq!(dances(&input.0, 5, 1), "baedc".to_string());
+        assert_eq!(dances(&input.0, 5, 2), "ceadb".to_string());
+    }
+}
@@ -0,0 +1,54 @@
+use linked_list::LinkedList;
+use crate::parse::{first_line, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+fn part1(steps: usize) -> usize {
+    let mut ring: LinkedList<usize> = LinkedList::new();
+    ring.push_front(0);
+    let mut current_pos = 0;
+    for n in 1..=2017 {
+        current_pos = (current_pos + steps) % ring.len();
+        ring.insert(current_pos + 1, n);
+        current_pos += 1;
+    }
+    let mut cursor = ring.cursor();
+    cursor.seek_forward(current_pos + 1);
+    *cursor.peek_next().unwrap()
+}
+
+fn part2(steps: usize) -> usize {
+    let mut current_pos = 0;
+    let mut last_after_zero = 0;
+    for n in 1..=50_000_000 {
+        current_pos = (current_pos + steps) % n;
+        if current_pos == 0 { last_after_zero = n; }
+        current_pos += 1;
+    }
+    last_after_zero
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = usize;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        let line = first_line(input)?;
+        parse_token(line, line, "integer").map_err(|e| e.at_line(1))
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(*input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(*input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day16_test() {
+        assert_eq!(part1(3), 638);
+    }
+}
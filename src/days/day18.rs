@@ -0,0 +1,64 @@
+use std::vec::Vec;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::vm::{parse_program, Dialect, Instruction, Network, RunResult, Topology, VM};
+
+fn part1(input: &[Instruction]) -> i64 {
+    let mut vm = VM::new(input);
+    let mut freq = 0_i64;
+    loop {
+        match vm.run() {
+            RunResult::Halt | RunResult::Break | RunResult::Fault { .. } => panic!(),
+            RunResult::Blocked(r) => {
+                if vm.get_reg(r) != 0 {
+                    return freq;
+                }
+                // rcv is a no-op on a zero register; feeding it its own
+                // value has the same effect
+                vm.inbox.push_back(0);
+            },
+            RunResult::Snd(v) => {
+                freq = v;
+            },
+            RunResult::Ok => {},
+        }
+    }
+}
+
+fn part2(input: &[Instruction]) -> usize {
+    let mut net = Network::new(input, 2, &Topology::Ring).unwrap();
+    net.run();
+    net.vms[1].sent
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Instruction>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_program(input, Dialect::Duet)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+    use crate::vm::{run_threaded, Arithmetic};
+
+    #[test]
+    fn day18_test() {
+        let input = Day18::parse(&test_input::<String>(include_str!("day18.testinput"))).unwrap();
+        assert_eq!(part1(&input), 4);
+        let input = Day18::parse(&test_input::<String>(include_str!("day18.testinput2"))).unwrap();
+        assert_eq!(part2(&input), 3);
+        let threaded = run_threaded(&input, 2, &Topology::Ring, 16, Arithmetic::Checked).unwrap();
+        assert_eq!(threaded.programs[1].sent, 3);
+    }
+}
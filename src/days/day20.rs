@@ -0,0 +1,119 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::vec::Vec;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::parse::{parse_lines, parse_token, ParseError};
+use crate::solution::{Answer, Solution};
+
+type Coord3D = (i64, i64, i64);
+
+#[derive(Clone)]
+pub struct Particle {
+    pos: Coord3D,
+    vel: Coord3D,
+    acc: Coord3D,
+}
+
+impl FromStr for Particle {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(
+                r"p=.(-?\d+),(-?\d+),(-?\d+)., v=.(-?\d+),(-?\d+),(-?\d+)., a=.(-?\d+),(-?\d+),(-?\d+)"
+            ).unwrap();
+        }
+        if let Some(caps) = RE.captures(s) {
+            let n = |i| parse_token::<i64>(s, caps.get(i).unwrap().as_str(), "integer");
+            let px = n(1)?;
+            let py = n(2)?;
+            let pz = n(3)?;
+            let vx = n(4)?;
+            let vy = n(5)?;
+            let vz = n(6)?;
+            let ax = n(7)?;
+            let ay = n(8)?;
+            let az = n(9)?;
+            Ok(Particle {
+                pos: (px, py, pz),
+                vel: (vx, vy, vz),
+                acc: (ax, ay, az),
+            })
+        }
+        else {
+            Err(ParseError::new(s, s, "p=<x,y,z>, v=<x,y,z>, a=<x,y,z>"))
+        }
+    }
+}
+
+impl Particle {
+    fn update(&mut self) {
+        self.vel.0 += self.acc.0;
+        self.vel.1 += self.acc.1;
+        self.vel.2 += self.acc.2;
+        self.pos.0 += self.vel.0;
+        self.pos.1 += self.vel.1;
+        self.pos.2 += self.vel.2;
+    }
+}
+
+fn part1(input: &[Particle]) -> usize {
+    input.iter()
+        .enumerate()
+        .map(|(idx, p)| (idx, p.acc.0.abs() + p.acc.1.abs() + p.acc.2.abs()))
+        .min_by_key(|(_, v)| *v)
+        .map(|(idx, _)| idx)
+        .unwrap()
+}
+
+fn part2(input: &[Particle]) -> usize {
+    let mut particles = input.to_owned();
+
+    for _ in 0..1000 {
+        let mut positions: HashMap<Coord3D, Vec<usize>> = HashMap::new();
+        for (idx, p) in particles.iter_mut().enumerate() {
+            p.update();
+            positions.entry(p.pos)
+                .and_modify(|e| e.push(idx))
+                .or_insert(vec![idx]);
+        }
+        for idx in positions.values()
+            .filter(|v| v.len() > 1)
+            .flatten()
+            .sorted_by_key(|v| Reverse(*v)) {
+                particles.remove(*idx);
+        }
+    }
+    particles.len()
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<Particle>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_lines(input)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day20_test() {
+        let input: Vec<Particle> = test_input(include_str!("day20.testinput"));
+        assert_eq!(part1(&input), 0);
+        let input: Vec<Particle> = test_input(include_str!("day20.testinput2"));
+        assert_eq!(part2(&input), 1);
+    }
+}
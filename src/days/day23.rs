@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::vec::Vec;
+use ya_advent_lib::math::is_prime;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::vm::{optimize, parse_program, symbolic, Dialect, Instruction, Registers, Rewrite, RI, VM};
+
+fn part1(input: &[Instruction]) -> usize {
+    let mut vm = VM::new(input);
+    let mut mul_count = 0;
+    let mut trap = |inst: &Instruction, _: &Registers| match inst {
+            Instruction::Mul(_,_) => {mul_count += 1; false},
+            _ => false,
+        };
+    vm.set_trap(&mut trap);
+    vm.run();
+    mul_count
+}
+
+// The tail of the outer loop, which counts b into h when the factor
+// search cleared f, then steps b towards c:
+//
+//   jnz f 2 / sub h -1 / set g b / sub g c / jnz g 2 / jnz 1 3
+//   sub b -17 / jnz 1 -N
+//
+// Returns (loop head, f, b, c, step).
+fn outer_loop(input: &[Instruction]) -> Option<(usize, char, char, char, usize)> {
+    use Instruction::*;
+    use RI::{Imm, Reg};
+    let [
+        .., Jnz(Reg(f), Imm(2)), Sub(_, Imm(-1)),
+        Set(g1, Reg(b1)), Sub(g2, Reg(c)), Jnz(Reg(g3), Imm(2)), Jnz(Imm(1), Imm(3)),
+        Sub(b2, Imm(step)), Jnz(Imm(1), Imm(back)),
+    ] = *input else {
+        return None;
+    };
+    let head = usize::try_from((input.len() as i64 - 1).checked_add(back)?).ok()?;
+    (g1 == g2 && g1 == g3 && b1 == b2 && step < 0).then_some((head, f, b1, c, -step as usize))
+}
+
+// With a=1 the program counts the composite numbers from b to c by
+// trial multiplication, which would take far too long. When the loop
+// has the expected shape, b and c come from evaluating the setup code
+// with a symbolic a, and the count is done directly. None if the loop
+// doesn't look like that, or b would never land on c.
+fn shortcut(input: &[Instruction], program: &[Instruction], rewrites: &[Rewrite]) -> Option<i64> {
+    let a = BTreeMap::from([('a', 1)]);
+    let (head, f, b, c, step) = outer_loop(input)?;
+    let searches_b = rewrites.iter()
+        .any(|r| program[r.pc] == Instruction::Composite(f, RI::Reg(b)) && r.pc > head);
+    if !searches_b {
+        return None;
+    }
+    let branch = symbolic(input, &['a'], head).ok()?
+        .into_iter()
+        .find(|br| br.holds(&a))?;
+    let (from, to) = (branch.value(b, &a)?, branch.value(c, &a)?);
+    if from > to || (to - from) % step as i64 != 0 {
+        return None;
+    }
+    Some((from..=to).step_by(step).filter(|n| *n > 3 && !is_prime(*n as u64)).count() as i64)
+}
+
+fn part2(input: &[Instruction]) -> i64 {
+    let (program, rewrites) = optimize(input);
+    if let Some(h) = shortcut(input, &program, &rewrites) {
+        return h;
+    }
+    // Otherwise the optimizer has swapped the search for a native check
+    // and the program can just run
+    let mut vm = VM::new(&program);
+    vm.set_reg('a', 1);
+    vm.run();
+    vm.get_reg('h')
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<Instruction>;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError> {
+        parse_program(input, Dialect::Coprocessor)
+    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input).into()
+    }
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(part2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day23_test() {
+        // The real program's shape with small numbers: b = 23, c = 57,
+        // stepping by 17, so h counts 40 and 57
+        let src = "set b 5\nset c b\njnz a 2\njnz 1 5\nmul b 4\nsub b -3\nset c b\nsub c -34\n\
+            set f 1\nset d 2\nset e 2\nset g d\nmul g e\nsub g b\njnz g 2\nset f 0\nsub e -1\n\
+            set g e\nsub g b\njnz g -8\nsub d -1\nset g d\nsub g b\njnz g -13\njnz f 2\nsub h -1\n\
+            set g b\nsub g c\njnz g 2\njnz 1 3\nsub b -17\njnz 1 -23";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let input = Day23::parse(&lines).unwrap();
+        let (program, rewrites) = optimize(&input);
+        assert_eq!(shortcut(&input, &program, &rewrites), Some(2));
+        assert_eq!(part2(&input), 2);
+        for prog in [&program, &input] {
+            let mut vm = VM::new(prog);
+            vm.set_reg('a', 1);
+            vm.run();
+            assert_eq!(vm.get_reg('h'), 2);
+        }
+
+        // With c = 53 b steps right past it
+        let lines: Vec<String> = src.replace("sub c -34", "sub c -30").lines().map(|l| l.to_string()).collect();
+        let input = Day23::parse(&lines).unwrap();
+        let (program, rewrites) = optimize(&input);
+        assert_eq!(shortcut(&input, &program, &rewrites), None);
+
+        let lines: Vec<String> = src.replace("jnz 1 -23", "jnz 1 9223372036854775807").lines().map(|l| l.to_string()).collect();
+        assert_eq!(outer_loop(&Day23::parse(&lines).unwrap()), None);
+    }
+}
@@ -0,0 +1,61 @@
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+use crate::parse::ParseError;
+use crate::runner::{self, DayResult};
+
+pub const LAST_DAY: u32 = 25;
+
+pub fn run(day: u32, input: &[String], parts: &[u32]) -> Option<Result<DayResult, ParseError>> {
+    match day {
+        1 => Some(runner::run::<day01::Day01>(day, input, parts)),
+        2 => Some(runner::run::<day02::Day02>(day, input, parts)),
+        3 => Some(runner::run::<day03::Day03>(day, input, parts)),
+        4 => Some(runner::run::<day04::Day04>(day, input, parts)),
+        5 => Some(runner::run::<day05::Day05>(day, input, parts)),
+        6 => Some(runner::run::<day06::Day06>(day, input, parts)),
+        7 => Some(runner::run::<day07::Day07>(day, input, parts)),
+        8 => Some(runner::run::<day08::Day08>(day, input, parts)),
+        9 => Some(runner::run::<day09::Day09>(day, input, parts)),
+        10 => Some(runner::run::<day10::Day10>(day, input, parts)),
+        11 => Some(runner::run::<day11::Day11>(day, input, parts)),
+        12 => Some(runner::run::<day12::Day12>(day, input, parts)),
+        13 => Some(runner::run::<day13::Day13>(day, input, parts)),
+        14 => Some(runner::run::<day14::Day14>(day, input, parts)),
+        15 => Some(runner::run::<day15::Day15>(day, input, parts)),
+        16 => Some(runner::run::<day16::Day16>(day, input, parts)),
+        17 => Some(runner::run::<day17::Day17>(day, input, parts)),
+        18 => Some(runner::run::<day18::Day18>(day, input, parts)),
+        19 => Some(runner::run::<day19::Day19>(day, input, parts)),
+        20 => Some(runner::run::<day20::Day20>(day, input, parts)),
+        21 => Some(runner::run::<day21::Day21>(day, input, parts)),
+        22 => Some(runner::run::<day22::Day22>(day, input, parts)),
+        23 => Some(runner::run::<day23::Day23>(day, input, parts)),
+        24 => Some(runner::run::<day24::Day24>(day, input, parts)),
+        25 => Some(runner::run::<day25::Day25>(day, input, parts)),
+        _ => None,
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::parse::ParseError;
+
+pub const STANDARD_SIZE: usize = 256;
+pub const STANDARD_ROUNDS: usize = 64;
+pub const STANDARD_SUFFIX: [usize; 5] = [17, 31, 73, 47, 23];
+pub const DIGEST_LEN: usize = 16;
+
+fn reverse_section(list: &mut [u8], start: usize, len: usize) {
+    if len <= 1 { return; }
+    for n in 0..len/2 {
+        let i = (start + n) % list.len();
+        let j = (start + len - n - 1) % list.len();
+        list.swap(i, j);
+    }
+}
+
+// Takes the lengths as an iterator so the hasher can widen its buffered
+// bytes on the fly instead of copying them all into usizes
+pub fn knot_hash_raw<I>(list: &mut [u8], lengths: I, rounds: usize)
+where I: IntoIterator<Item = usize>, I::IntoIter: Clone {
+    let lengths = lengths.into_iter();
+    let mut current_pos = 0usize;
+    let mut skip_size = 0usize;
+    //println!("{list:?}");
+    for _ in 0..rounds {
+        for l in lengths.clone() {
+            reverse_section(list, current_pos, l);
+            //println!("{list:?}");
+            current_pos += l + skip_size;
+            current_pos %= list.len();
+            skip_size += 1;
+        }
+    }
+}
+
+pub fn knot_hash(input: &str) -> Vec<u8> {
+    let mut hasher = KnotHasher::new();
+    hasher.update(input.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Digest(pub [u8; DIGEST_LEN]);
+
+impl Digest {
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Digest {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((idx, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(ParseError::new(s, &s[idx..idx + c.len_utf8()], "hex digit"));
+        }
+        if s.len() != DIGEST_LEN * 2 {
+            return Err(ParseError::new(s, s, "32 hex digits"));
+        }
+        let mut bytes = [0u8; DIGEST_LEN];
+        for (idx, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[idx * 2 .. idx * 2 + 2], 16).unwrap();
+        }
+        Ok(Digest(bytes))
+    }
+}
+
+// The whole length sequence is replayed on every round, so update()
+// only buffers; the rounds all happen in finalize().
+#[derive(Clone, Debug)]
+pub struct KnotHasher {
+    size: usize,
+    rounds: usize,
+    suffix: Vec<usize>,
+    // The input bytes, widened to lengths only when hashing
+    lengths: Vec<u8>,
+}
+
+impl Default for KnotHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KnotHasher {
+    pub fn new() -> Self {
+        Self {
+            size: STANDARD_SIZE,
+            rounds: STANDARD_ROUNDS,
+            suffix: STANDARD_SUFFIX.to_vec(),
+            lengths: Vec::new(),
+        }
+    }
+    // The ring is folded into DIGEST_LEN blocks, so the size must be a
+    // multiple of that, and its elements must fit in a u8.
+    pub fn with_size(mut self, size: usize) -> Self {
+        assert!(size > 0 && size <= 256 && size.is_multiple_of(DIGEST_LEN));
+        self.size = size;
+        self
+    }
+    pub fn with_rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+    pub fn with_suffix(mut self, suffix: &[usize]) -> Self {
+        self.suffix = suffix.to_vec();
+        self
+    }
+    pub fn update(&mut self, data: &[u8]) {
+        self.lengths.extend_from_slice(data);
+    }
+    pub fn sparse(&self) -> Vec<u8> {
+        let lengths = self.lengths.iter()
+            .map(|b| *b as usize)
+            .chain(self.suffix.iter().copied());
+        let mut list: Vec<u8> = (0..self.size).map(|n| n as u8).collect();
+        knot_hash_raw(&mut list, lengths, self.rounds);
+        list
+    }
+    pub fn finalize(self) -> Digest {
+        let list = self.sparse();
+        let mut digest = [0u8; DIGEST_LEN];
+        list.chunks(self.size / DIGEST_LEN)
+            .zip(digest.iter_mut())
+            .for_each(|(slice, d)| *d = slice.iter().fold(0, |acc, i| acc ^ i));
+        Digest(digest)
+    }
+}
+
+// So a reader can be hashed with io::copy
+impl io::Write for KnotHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knot_hasher_test() {
+        let mut h = KnotHasher::new();
+        h.update(b"AoC ");
+        h.update(b"2017");
+        let d = h.finalize();
+        assert_eq!(d.to_string(), "33efeb34ea91902bb2f59c9920caa6cd");
+        assert_eq!(d.to_vec(), knot_hash("AoC 2017"));
+        let mut h = KnotHasher::new();
+        io::copy(&mut &b"AoC 2017"[..], &mut h).unwrap();
+        assert_eq!(h.finalize(), d);
+        assert_eq!("33efeb34ea91902bb2f59c9920caa6cd".parse::<Digest>(), Ok(d));
+        assert!("33efeb34ea91902bb2f59c9920caa6c".parse::<Digest>().is_err());
+        assert!("33efeb34ea91902bb2f59c9920caa6cx".parse::<Digest>().is_err());
+
+        let h = KnotHasher::new().with_size(16).with_rounds(1).with_suffix(&[]);
+        let mut list = Vec::from_iter(0u8..16);
+        knot_hash_raw(&mut list, [], 1);
+        assert_eq!(h.sparse(), list);
+        assert_eq!(h.finalize().0, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    }
+}
@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::vec::Vec;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub token: String,
+    pub expected: String,
+}
+
+// Byte offset of `token` within `line`, when token is a subslice of it.
+fn offset_in(line: &str, token: &str) -> Option<usize> {
+    let start = line.as_ptr() as usize;
+    let tok = token.as_ptr() as usize;
+    if tok >= start && tok + token.len() <= start + line.len() {
+        Some(tok - start)
+    } else {
+        None
+    }
+}
+
+impl ParseError {
+    // `token` should be a slice of `line` so the column can be located.
+    pub fn new(line: &str, token: &str, expected: &str) -> Self {
+        Self {
+            line: None,
+            column: offset_in(line, token).unwrap_or(0) + 1,
+            token: token.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+    pub fn eol(line: &str, expected: &str) -> Self {
+        Self {
+            line: None,
+            column: line.len() + 1,
+            token: String::new(),
+            expected: expected.to_string(),
+        }
+    }
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+    // Rebase an error produced while parsing `token` on its own so the
+    // column refers to the enclosing `line`.
+    pub fn within(mut self, line: &str, token: &str) -> Self {
+        self.column += offset_in(line, token).unwrap_or(0);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+        write!(f, "column {}: expected {}, found ", self.column, self.expected)?;
+        if self.token.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "\"{}\"", self.token)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+pub fn next_token<'a, I>(line: &str, iter: &mut I, expected: &str) -> Result<&'a str, ParseError>
+where I: Iterator<Item = &'a str> {
+    iter.next().ok_or_else(|| ParseError::eol(line, expected))
+}
+
+pub fn parse_token<T: FromStr>(line: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token.parse::<T>().map_err(|_| ParseError::new(line, token, expected))
+}
+
+pub fn first_line(lines: &[String]) -> Result<&str, ParseError> {
+    lines.first()
+        .map(|l| l.as_str())
+        .ok_or_else(|| ParseError::eol("", "input").at_line(1))
+}
+
+pub fn parse_first<T>(lines: &[String]) -> Result<T, ParseError>
+where T: FromStr<Err = ParseError> {
+    first_line(lines)?.parse::<T>().map_err(|e| e.at_line(1))
+}
+
+// For grid inputs: reject any character that `valid` does not accept.
+pub fn check_chars<F>(lines: &[String], valid: F, expected: &str) -> Result<(), ParseError>
+where F: Fn(char) -> bool {
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some((col, c)) = line.char_indices().find(|(_, c)| !valid(*c)) {
+            let token = &line[col..col + c.len_utf8()];
+            return Err(ParseError::new(line, token, expected).at_line(idx + 1));
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_lines<T>(lines: &[String]) -> Result<Vec<T>, ParseError>
+where T: FromStr<Err = ParseError> {
+    lines.iter()
+        .enumerate()
+        .map(|(idx, l)| l.parse::<T>().map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
+
+// For inputs that are one plain number per line.
+pub fn parse_number_lines<T: FromStr>(lines: &[String]) -> Result<Vec<T>, ParseError> {
+    lines.iter()
+        .enumerate()
+        .map(|(idx, l)| parse_token(l, l, "integer").map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pair(i64, i64);
+    impl FromStr for Pair {
+        type Err = ParseError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let mut itr = s.split(' ');
+            let a = parse_token(s, next_token(s, &mut itr, "integer")?, "integer")?;
+            let b = parse_token(s, next_token(s, &mut itr, "integer")?, "integer")?;
+            Ok(Pair(a, b))
+        }
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let lines: Vec<String> = vec!["1 2".into(), "3 4".into()];
+        let pairs: Vec<Pair> = parse_lines(&lines).unwrap();
+        assert_eq!(pairs[1].0 + pairs[1].1, 7);
+
+        let lines: Vec<String> = vec!["1 2".into(), "3 x4".into()];
+        let err = parse_lines::<Pair>(&lines).err().unwrap();
+        assert_eq!((err.line, err.column, err.token.as_str()), (Some(2), 3, "x4"));
+        assert_eq!(err.to_string(), "line 2, column 3: expected integer, found \"x4\"");
+
+        let lines: Vec<String> = vec!["12".into()];
+        let err = parse_lines::<Pair>(&lines).err().unwrap();
+        assert_eq!(err.to_string(), "line 1, column 3: expected integer, found end of line");
+
+        let err = ParseError::new("x", "x", "digit").within("ab x", &"ab x"[3..]);
+        assert_eq!(err.column, 4);
+    }
+}
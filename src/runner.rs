@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use ya_advent_lib::read::input_lines;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+pub struct PartResult {
+    pub part: u32,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+pub struct DayResult {
+    pub day: u32,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl PartResult {
+    pub fn to_json(&self, day: u32) -> String {
+        format!(
+            "{{\"day\":{day},\"part\":{},\"answer\":{},\"elapsed_ns\":{}}}",
+            self.part, self.answer.to_json(), self.elapsed.as_nanos(),
+        )
+    }
+}
+
+impl DayResult {
+    pub fn elapsed(&self) -> Duration {
+        self.parse_elapsed + self.parts.iter().map(|p| p.elapsed).sum::<Duration>()
+    }
+}
+
+pub fn run<S: Solution>(day: u32, input: &[String], parts: &[u32]) -> Result<DayResult, ParseError> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_elapsed = start.elapsed();
+    let results = parts.iter()
+        .filter_map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => Some(S::part1(&parsed)),
+                2 => S::part2(&parsed),
+                _ => None,
+            };
+            answer.map(|answer| PartResult { part, answer, elapsed: start.elapsed() })
+        })
+        .collect();
+    Ok(DayResult { day, parse_elapsed, parts: results })
+}
+
+// Entry point for the standalone dayNN binaries
+pub fn main<S: Solution>(day: u32) {
+    let input: Vec<String> = input_lines().collect();
+    let result = match run::<S>(day, &input, &[1, 2]) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Invalid input: {e}");
+            std::process::exit(1);
+        },
+    };
+    for p in result.parts {
+        println!("Part {}: {}", p.part, p.answer);
+    }
+}
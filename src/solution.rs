@@ -0,0 +1,96 @@
+use std::fmt;
+use crate::parse::ParseError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl Answer {
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Int(n) => n.to_string(),
+            Answer::Str(s) => json_string(s),
+        }
+    }
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+pub trait Solution {
+    type Input;
+    fn parse(input: &[String]) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(_input: &Self::Input) -> Option<Answer> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_json_test() {
+        assert_eq!(Answer::from(-42i64).to_json(), "-42");
+        assert_eq!(Answer::from(7usize).to_json(), "7");
+        assert_eq!(Answer::from("tknk").to_json(), "\"tknk\"");
+        assert_eq!(Answer::from("a\"b\\c\n\u{1}").to_json(), "\"a\\\"b\\\\c\\n\\u0001\"");
+    }
+}
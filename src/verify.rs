@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::str::FromStr;
+use toml::{Table, Value};
+use crate::days::LAST_DAY;
+use crate::solution::Answer;
+
+// Reads a file of [dayNN] tables like the one below, which bench
+// baselines share. `value` turns each entry into a T, given its
+// `dayNN.partN` key for error messages.
+pub fn parse_day_tables<T, F>(s: &str, mut value: F) -> Result<BTreeMap<u32, [Option<T>; 2]>, String>
+where F: FnMut(&str, &Value) -> Result<T, String> {
+    let table = s.parse::<Table>().map_err(|e| e.to_string())?;
+    let mut days = BTreeMap::new();
+    for (key, parts) in table.iter() {
+        let day = key.strip_prefix("day")
+            .and_then(|d| d.parse::<u32>().ok())
+            .ok_or_else(|| format!("{key}: expected a [dayNN] table"))?;
+        if !(1..=LAST_DAY).contains(&day) {
+            return Err(format!("{key}: no such day, expected 1-{LAST_DAY}"));
+        }
+        let parts = parts.as_table()
+            .ok_or_else(|| format!("{key}: expected a [dayNN] table"))?;
+        let mut entry: [Option<T>; 2] = [None, None];
+        for (pkey, v) in parts.iter() {
+            let idx = match pkey.as_str() {
+                "part1" => 0,
+                "part2" => 1,
+                _ => return Err(format!("{key}.{pkey}: expected part1 or part2")),
+            };
+            entry[idx] = Some(value(&format!("{key}.{pkey}"), v)?);
+        }
+        days.insert(day, entry);
+    }
+    Ok(days)
+}
+
+// Known-good answers, loaded from a file like:
+//
+//   [day07]
+//   part1 = "tknk"
+//   part2 = 60
+pub struct ExpectedAnswers(BTreeMap<u32, [Option<Answer>; 2]>);
+
+impl ExpectedAnswers {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+        text.parse::<Self>().map_err(|e| format!("{path}: {e}"))
+    }
+    pub fn days(&self) -> Vec<u32> {
+        self.0.keys().copied().collect()
+    }
+    pub fn get(&self, day: u32, part: u32) -> Option<&Answer> {
+        self.0.get(&day)
+            .and_then(|parts| parts.get(part as usize - 1))
+            .and_then(|a| a.as_ref())
+    }
+}
+
+fn answer_from_value(key: &str, v: &Value) -> Result<Answer, String> {
+    match v {
+        Value::Integer(n) => Ok(Answer::Int(*n)),
+        Value::String(s) => Ok(Answer::Str(s.clone())),
+        _ => Err(format!("{key}: expected integer or string")),
+    }
+}
+
+impl FromStr for ExpectedAnswers {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_day_tables(s, answer_from_value).map(Self)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Mismatch { expected: Answer, actual: Answer },
+    Unchecked(Answer),
+    // There's an expected answer but the solution has no such part
+    Missing(Answer),
+}
+
+pub fn check(expected: Option<&Answer>, actual: &Answer) -> Verdict {
+    match expected {
+        Some(e) if e == actual => Verdict::Pass,
+        Some(e) => Verdict::Mismatch { expected: e.clone(), actual: actual.clone() },
+        None => Verdict::Unchecked(actual.clone()),
+    }
+}
+
+// For a part the solution didn't produce: only a problem when an
+// answer was expected
+pub fn check_missing(expected: Option<&Answer>) -> Option<Verdict> {
+    expected.map(|e| Verdict::Missing(e.clone()))
+}
+
+// Expected/actual lines with a caret under the first differing character.
+pub fn diff(expected: &Answer, actual: &Answer) -> String {
+    let e = expected.to_string();
+    let a = actual.to_string();
+    let col = e.chars()
+        .zip(a.chars())
+        .position(|(x, y)| x != y)
+        .unwrap_or(e.chars().count().min(a.chars().count()));
+    format!("expected: {e}\nactual:   {a}\n          {}^", " ".repeat(col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_test() {
+        let answers = "[day07]\npart1 = \"tknk\"\npart2 = 60\n\n[day25]\npart1 = 3\n"
+            .parse::<ExpectedAnswers>()
+            .unwrap();
+        assert_eq!(answers.days(), vec![7, 25]);
+        assert_eq!(check(answers.get(7, 1), &"tknk".into()), Verdict::Pass);
+        assert_eq!(
+            check(answers.get(7, 2), &Answer::Int(61)),
+            Verdict::Mismatch { expected: Answer::Int(60), actual: Answer::Int(61) },
+        );
+        assert_eq!(check(answers.get(25, 2), &Answer::Int(1)), Verdict::Unchecked(Answer::Int(1)));
+        assert_eq!(check_missing(answers.get(25, 1)), Some(Verdict::Missing(Answer::Int(3))));
+        assert_eq!(check_missing(answers.get(25, 2)), None);
+        assert_eq!(diff(&Answer::Int(1234), &Answer::Int(1284)), "expected: 1234\nactual:   1284\n            ^");
+
+        assert!("[day07]\npart3 = 1\n".parse::<ExpectedAnswers>().is_err());
+        assert!("[day07]\npart1 = 1.5\n".parse::<ExpectedAnswers>().is_err());
+        assert!("[seven]\npart1 = 1\n".parse::<ExpectedAnswers>().is_err());
+        assert_eq!("[day26]\npart1 = 1\n".parse::<ExpectedAnswers>().err().unwrap(), "day26: no such day, expected 1-25");
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::vec::Vec;
+use crate::parse::ParseError;
+use crate::vm::isa::{Dialect, Instruction};
+
+// An assembled program along with the names it was written with
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Assembly {
+    pub program: Vec<Instruction>,
+    // The VM register standing in for each register name in the source
+    pub registers: BTreeMap<String, char>,
+    // The pc each label points at
+    pub labels: BTreeMap<String, usize>,
+}
+
+// The VM register a name on the command line or in the debugger
+// stands for: a name from the source, or any single letter that wasn't
+// handed to one of those, since writing it would change that register.
+pub fn resolve_register(registers: &BTreeMap<String, char>, name: &str) -> Option<char> {
+    match name.as_bytes() {
+        [c @ b'a'..=b'z'] => {
+            let c = *c as char;
+            registers.iter().all(|(n, r)| *r != c || n.len() == 1).then_some(c)
+        },
+        _ => registers.get(name).copied(),
+    }
+}
+
+impl Assembly {
+    pub fn register(&self, name: &str) -> Option<char> {
+        resolve_register(&self.registers, name)
+    }
+}
+
+// One instruction's worth of source
+struct Line<'a> {
+    text: &'a str,
+    number: usize,
+    // Everything before the comment
+    code: &'a str,
+    tokens: Vec<&'a str>,
+}
+
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_jump(op: &str) -> bool {
+    op == "jgz" || op == "jnz"
+}
+
+// Splits off labels and comments, and records where each label points.
+fn scan(lines: &[String]) -> Result<(Vec<Line<'_>>, BTreeMap<String, usize>), ParseError> {
+    let mut out = Vec::new();
+    let mut labels = BTreeMap::new();
+    for (idx, text) in lines.iter().enumerate() {
+        let code = &text[..text.find([';', '#']).unwrap_or(text.len())];
+        let mut tokens: Vec<&str> = code.split_whitespace().collect();
+        while let Some(label) = tokens.first().and_then(|t| t.strip_suffix(':')) {
+            if !is_name(label) {
+                return Err(ParseError::new(text, tokens[0], "label name").at_line(idx + 1));
+            }
+            if labels.insert(label.to_string(), out.len()).is_some() {
+                return Err(ParseError::new(text, tokens[0], "new label").at_line(idx + 1));
+            }
+            tokens.remove(0);
+        }
+        if !tokens.is_empty() {
+            out.push(Line { text, number: idx + 1, code, tokens });
+        }
+    }
+    Ok((out, labels))
+}
+
+// Gives every register name a VM register. Single letters are kept as
+// they are, so a plain program assembles to itself; longer names take
+// the letters left over, in order of first use. In the duet dialect p
+// is never handed out since the network presets it.
+fn allocate(lines: &[Line], labels: &BTreeMap<String, usize>, dialect: Dialect)
+    -> Result<BTreeMap<String, char>, ParseError>
+{
+    let mut names: Vec<(&str, &Line)> = Vec::new();
+    for line in lines {
+        for (i, t) in line.tokens.iter().enumerate().skip(1) {
+            let offset = i == 2 && is_jump(line.tokens[0]);
+            if !is_name(t) || (offset && labels.contains_key(*t)) {
+                continue;
+            }
+            if labels.contains_key(*t) {
+                return Err(ParseError::new(line.text, t, "register, not a label").at_line(line.number));
+            }
+            if offset && t.len() > 1 {
+                return Err(ParseError::new(line.text, t, "label").at_line(line.number));
+            }
+            if !names.iter().any(|(n, _)| n == t) {
+                names.push((t, line));
+            }
+        }
+    }
+    let mut registers: BTreeMap<String, char> = names.iter()
+        .filter_map(|(n, _)| match n.as_bytes() {
+            [c @ b'a'..=b'z'] => Some((n.to_string(), *c as char)),
+            _ => None,
+        })
+        .collect();
+    let reserved = if dialect == Dialect::Duet { Some('p') } else { None };
+    let taken: Vec<char> = registers.values().copied().collect();
+    let mut free = ('a'..='z').filter(|c| Some(*c) != reserved && !taken.contains(c));
+    for (name, line) in names {
+        if !registers.contains_key(name) {
+            let r = free.next()
+                .ok_or_else(|| ParseError::new(line.text, name, "one of at most 26 registers").at_line(line.number))?;
+            registers.insert(name.to_string(), r);
+        }
+    }
+    Ok(registers)
+}
+
+// Lowers one line to the plain `op x y` form and parses that, pointing
+// any error back at the original source.
+fn lower(line: &Line, pc: usize, labels: &BTreeMap<String, usize>, registers: &BTreeMap<String, char>, dialect: Dialect)
+    -> Result<Instruction, ParseError>
+{
+    let lowered: Vec<String> = line.tokens.iter()
+        .enumerate()
+        .map(|(i, t)| match (labels.get(*t), registers.get(*t)) {
+            (Some(target), _) if i == 2 && is_jump(line.tokens[0]) => (*target as i64 - pc as i64).to_string(),
+            (_, Some(r)) if i > 0 => r.to_string(),
+            _ => t.to_string(),
+        })
+        .collect();
+    let text = lowered.join(" ");
+    let inst = Instruction::parse(&text, dialect).map_err(|e| {
+        if e.token.is_empty() {
+            return ParseError::eol(line.code.trim_end(), &e.expected).at_line(line.number);
+        }
+        let mut start = 1;
+        let i = lowered.iter().position(|t| {
+            start += t.len() + 1;
+            e.column < start
+        });
+        let token = line.tokens[i.unwrap_or(0)];
+        ParseError::new(line.text, token, &e.expected).at_line(line.number)
+    })?;
+    let arity = inst.to_string().split(' ').count();
+    match line.tokens.get(arity) {
+        Some(extra) => Err(ParseError::new(line.text, extra, "end of line").at_line(line.number)),
+        None => Ok(inst),
+    }
+}
+
+// Assembles source written with labels (`loop:`, then `jnz g loop`),
+// `;` or `#` comments and register names of any length. Plain programs
+// are valid source too and assemble to the same instructions. A jump
+// offset has to be a number, a label or a single-letter register.
+pub fn assemble(lines: &[String], dialect: Dialect) -> Result<Assembly, ParseError> {
+    let (lines, labels) = scan(lines)?;
+    let registers = allocate(&lines, &labels, dialect)?;
+    let program = lines.iter()
+        .enumerate()
+        .map(|(pc, line)| lower(line, pc, &labels, &registers, dialect))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Assembly { program, registers, labels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, VM};
+
+    fn lines(src: &str) -> Vec<String> {
+        src.lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn assemble_test() {
+        let src = "\
+; sum 1..=n into total
+        set n 4
+        set total 0
+loop:   add total n     # accumulate
+        add n -1
+        jgz n loop
+done:";
+        let asm = assemble(&lines(src), Dialect::Duet).unwrap();
+        assert_eq!(asm.registers["n"], 'n');
+        assert_eq!(asm.registers["total"], 'a');
+        assert_eq!(asm.labels["loop"], 2);
+        assert_eq!(asm.labels["done"], 5);
+        let expected = parse_program(&lines("set n 4\nset a 0\nadd a n\nadd n -1\njgz n -2"), Dialect::Duet).unwrap();
+        assert_eq!(asm.program, expected);
+        let mut vm = VM::new(&asm.program);
+        vm.run();
+        assert_eq!(vm.get_reg(asm.registers["total"]), 10);
+        assert_eq!((asm.register("total"), asm.register("n"), asm.register("z")), (Some('a'), Some('n'), Some('z')));
+        assert_eq!((asm.register("a"), asm.register("count")), (None, None));
+
+        // p stays free for the network's program id
+        let asm = assemble(&lines("set a 1\nset b 2\nset c 3\nset d 4\nset e 5\nset f 6\nset g 7\n\
+            set h 8\nset i 9\nset j 10\nset k 11\nset l 12\nset m 13\nset n 14\nset o 15\nset count 0"), Dialect::Duet)
+            .unwrap();
+        assert_eq!(asm.registers["count"], 'q');
+
+        // Plain programs come out unchanged
+        let plain = lines("set b 67\nset c b\njnz a 2\njnz 1 5\nmul b 100");
+        assert_eq!(assemble(&plain, Dialect::Coprocessor).unwrap().program,
+            parse_program(&plain, Dialect::Coprocessor).unwrap());
+
+        let err = |src: &str| assemble(&lines(src), Dialect::Coprocessor).unwrap_err().to_string();
+        assert_eq!(err("top: set g 1\njnz g bottom"), "line 2, column 7: expected label, found \"bottom\"");
+        assert_eq!(err("top: set top 1"), "line 1, column 10: expected register, not a label, found \"top\"");
+        assert_eq!(err("x: set a 1\nx: set a 2"), "line 2, column 1: expected new label, found \"x:\"");
+        assert_eq!(err("set count 1 2"), "line 1, column 13: expected end of line, found \"2\"");
+        assert_eq!(err("set count ; nothing"), "line 1, column 10: expected register or integer, found end of line");
+        assert_eq!(err("snd counter"), "line 1, column 1: expected coprocessor opcode, found \"snd\"");
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::vec::Vec;
+use crate::vm::isa::{Instruction, RI};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    Block(usize),
+    // Jumps or falls off either end of the program
+    Exit,
+    // A jump whose offset comes from a register
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub to: Target,
+    // False for falling through to the next instruction
+    pub jump: bool,
+    // Whether the jump depends on a register
+    pub conditional: bool,
+}
+
+// Instructions start..end, where only the last one can jump
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub edges: Vec<Edge>,
+}
+
+pub struct Cfg<'a> {
+    pub program: &'a [Instruction],
+    pub blocks: Vec<Block>,
+}
+
+// (condition, offset, is jgz) of a jump; `None` for anything else
+fn jump(inst: &Instruction) -> Option<(RI, RI, bool)> {
+    match *inst {
+        Instruction::Jgz(x, y) => Some((x, y, true)),
+        Instruction::Jnz(x, y) => Some((x, y, false)),
+        _ => None,
+    }
+}
+
+// Whether a jump with constant condition `x` is always or never taken
+fn constant(x: i64, greater: bool) -> bool {
+    if greater { x > 0 } else { x != 0 }
+}
+
+impl<'a> Cfg<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        let len = program.len() as i64;
+        let mut leaders = BTreeSet::new();
+        if !program.is_empty() {
+            leaders.insert(0);
+        }
+        for (pc, inst) in program.iter().enumerate() {
+            if let Some((_, y, _)) = jump(inst) {
+                if let RI::Imm(off) = y {
+                    match (pc as i64).checked_add(off) {
+                        Some(t) if t >= 0 && t < len => { leaders.insert(t as usize); },
+                        _ => {},
+                    }
+                }
+                if pc + 1 < program.len() {
+                    leaders.insert(pc + 1);
+                }
+            }
+        }
+        let starts: Vec<usize> = leaders.into_iter().collect();
+        let block_at = |pc: i64| match starts.binary_search(&(pc as usize)) {
+            Ok(b) if pc >= 0 && pc < len => Target::Block(b),
+            _ => Target::Exit,
+        };
+        let blocks = starts.iter()
+            .enumerate()
+            .map(|(b, &start)| {
+                let end = starts.get(b + 1).copied().unwrap_or(program.len());
+                let last = end - 1;
+                let next = Edge { to: block_at(end as i64), jump: false, conditional: false };
+                let edges = match jump(&program[last]) {
+                    None => vec![next],
+                    Some((x, y, greater)) => {
+                        let to = match y {
+                            // An offset that overflows leaves the program too
+                            RI::Imm(off) => (last as i64).checked_add(off).map_or(Target::Exit, block_at),
+                            RI::Reg(_) => Target::Unknown,
+                        };
+                        match x {
+                            RI::Imm(x) if constant(x, greater) => vec![Edge { to, jump: true, conditional: false }],
+                            RI::Imm(_) => vec![next],
+                            RI::Reg(_) => vec![
+                                Edge { to, jump: true, conditional: true },
+                                Edge { conditional: true, ..next },
+                            ],
+                        }
+                    },
+                };
+                Block { start, end, edges }
+            })
+            .collect();
+        Self { program, blocks }
+    }
+
+    pub fn block_of(&self, pc: usize) -> Option<usize> {
+        self.blocks.iter().position(|b| b.start <= pc && pc < b.end)
+    }
+
+    // Blocks that can run, following edges from the entry. Once a
+    // computed jump is reachable it could land anywhere, so everything
+    // counts as reachable.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut seen = vec![false; self.blocks.len()];
+        let mut todo: Vec<usize> = if self.blocks.is_empty() { vec![] } else { vec![0] };
+        while let Some(b) = todo.pop() {
+            if seen[b] {
+                continue;
+            }
+            seen[b] = true;
+            for e in &self.blocks[b].edges {
+                match e.to {
+                    Target::Block(t) => todo.push(t),
+                    Target::Unknown => return vec![true; self.blocks.len()],
+                    Target::Exit => {},
+                }
+            }
+        }
+        seen
+    }
+
+    // Graphviz source with one box per block, listing its instructions
+    // by pc so it can be read next to the disassembly. Conditional edges
+    // are labelled T and F, computed jumps go to a `?` node, and blocks
+    // that can never run are grey.
+    pub fn dot(&self) -> String {
+        let mut out = String::new();
+        let reachable = self.reachable();
+        let has = |t: Target| self.blocks.iter().any(|b| b.edges.iter().any(|e| e.to == t));
+        writeln!(out, "digraph program {{").unwrap();
+        writeln!(out, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+        for (b, block) in self.blocks.iter().enumerate() {
+            let mut label = String::new();
+            for pc in block.start..block.end {
+                write!(label, "{pc:>3}: {}\\l", self.program[pc]).unwrap();
+            }
+            let style = if reachable[b] { "" } else { ", style=filled, fillcolor=lightgrey" };
+            writeln!(out, "    b{b} [label=\"{label}\"{style}];").unwrap();
+        }
+        if has(Target::Exit) {
+            writeln!(out, "    exit [shape=oval];").unwrap();
+        }
+        if has(Target::Unknown) {
+            writeln!(out, "    unknown [shape=diamond, label=\"?\"];").unwrap();
+        }
+        for (b, block) in self.blocks.iter().enumerate() {
+            for e in &block.edges {
+                let to = match e.to {
+                    Target::Block(t) => format!("b{t}"),
+                    Target::Exit => "exit".to_string(),
+                    Target::Unknown => "unknown".to_string(),
+                };
+                let mut attrs = Vec::new();
+                if e.conditional {
+                    attrs.push(if e.jump { "label=\"T\"" } else { "label=\"F\"" });
+                }
+                if e.to == Target::Unknown {
+                    attrs.push("style=dashed");
+                }
+                if attrs.is_empty() {
+                    writeln!(out, "    b{b} -> {to};").unwrap();
+                } else {
+                    writeln!(out, "    b{b} -> {to} [{}];", attrs.join(", ")).unwrap();
+                }
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    #[test]
+    fn cfg_test() {
+        let lines: Vec<String> = "set a 3\nadd a -1\njgz a -1\njgz 1 2\nsnd a\njgz b a\nrcv a"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let cfg = Cfg::new(&prog);
+        let spans: Vec<(usize, usize)> = cfg.blocks.iter().map(|b| (b.start, b.end)).collect();
+        assert_eq!(spans, vec![(0, 1), (1, 3), (3, 4), (4, 5), (5, 6), (6, 7)]);
+        assert_eq!(cfg.blocks[1].edges, vec![
+            Edge { to: Target::Block(1), jump: true, conditional: true },
+            Edge { to: Target::Block(2), jump: false, conditional: true },
+        ]);
+        assert_eq!(cfg.blocks[2].edges, vec![Edge { to: Target::Block(4), jump: true, conditional: false }]);
+        assert_eq!(cfg.blocks[4].edges[0].to, Target::Unknown);
+        assert_eq!(cfg.blocks[5].edges[0].to, Target::Exit);
+        assert_eq!(cfg.block_of(2), Some(1));
+        // snd a is skipped, but the computed jump could reach it
+        assert_eq!(cfg.reachable(), vec![true; 6]);
+
+        let dot = cfg.dot();
+        assert!(dot.contains("    b1 [label=\"  1: add a -1\\l  2: jgz a -1\\l\"];\n"));
+        assert!(dot.contains("    b1 -> b1 [label=\"T\"];\n    b1 -> b2 [label=\"F\"];\n"));
+        assert!(dot.contains("    b4 -> unknown [label=\"T\", style=dashed];\n"));
+
+        let lines: Vec<String> = "jnz 1 2\nset a 1\nset b 2".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let cfg = Cfg::new(&prog);
+        assert_eq!(cfg.reachable(), vec![true, false, true]);
+        assert!(cfg.dot().contains("    b1 [label=\"  1: set a 1\\l\", style=filled, fillcolor=lightgrey];\n"));
+
+        let lines: Vec<String> = "set b 1\njnz 1 9223372036854775807".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        assert_eq!(Cfg::new(&prog).blocks[0].edges[0].to, Target::Exit);
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, BufRead, Write};
+use std::vec::Vec;
+use crate::vm::asm::resolve_register;
+use crate::vm::isa::{Instruction, Opcode};
+use crate::vm::machine::{Arithmetic, FaultKind, RunResult, VM};
+
+const HELP: &str = "\
+commands:
+  s, step [N]        execute N instructions (default 1)
+  c, continue        run until a breakpoint, watchpoint, rcv, fault
+                     or halt
+  b, break PC|OP     stop before the instruction at PC, or any OP
+  d, delete PC|OP    remove a breakpoint
+  w, watch REG       stop after REG changes
+  unwatch REG        remove a watchpoint
+  r, regs            dump registers
+  set REG VALUE      change a register
+  send VALUE         queue a value for rcv
+  arith [MODE]       show or set overflow handling: checked, wrapping
+                     or saturating
+  t, trace [N]       show the last N executed instructions
+  l, list [PC]       show the program around PC
+  info               show breakpoints and watchpoints
+  q, quit            exit
+an empty line repeats the previous command";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Breakpoint {
+    Pc(i64),
+    Op(Opcode),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Stop {
+    Breakpoint,
+    Watch(char, i64, i64),
+    Blocked(char),
+    Fault(FaultKind),
+    Halted,
+}
+
+// Drives a VM from line-oriented commands, so it works both at a
+// terminal and from a script piped into stdin.
+pub struct Debugger<'a> {
+    pub vm: VM<'a>,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<char>,
+    trace: VecDeque<(i64, Instruction)>,
+    trace_len: usize,
+    steps: usize,
+    // Register names from the source, for set and watch
+    registers: BTreeMap<String, char>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(vm: VM<'a>, trace_len: usize) -> Self {
+        Self {
+            vm,
+            breakpoints: Vec::new(),
+            watches: Vec::new(),
+            trace: VecDeque::with_capacity(trace_len),
+            trace_len,
+            steps: 0,
+            registers: BTreeMap::new(),
+        }
+    }
+    pub fn with_registers(mut self, registers: BTreeMap<String, char>) -> Self {
+        self.registers = registers;
+        self
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        let Some(inst) = self.current() else { return false };
+        self.breakpoints.iter().any(|b| match b {
+            Breakpoint::Pc(pc) => *pc == self.vm.pc,
+            Breakpoint::Op(op) => *op == inst.opcode(),
+        })
+    }
+
+    fn current(&self) -> Option<&'a Instruction> {
+        if self.vm.is_halted() {
+            None
+        } else {
+            Some(&self.vm.program[self.vm.pc as usize])
+        }
+    }
+
+    // Executes one instruction, reporting anything that should stop a
+    // continue.
+    fn exec_one<W: Write>(&mut self, out: &mut W) -> io::Result<Option<Stop>> {
+        let Some(inst) = self.current() else { return Ok(Some(Stop::Halted)) };
+        let pc = self.vm.pc;
+        let before: Vec<i64> = self.watches.iter().map(|r| self.vm.get_reg(*r)).collect();
+        let result = self.vm.step();
+        match result {
+            RunResult::Blocked(r) => return Ok(Some(Stop::Blocked(r))),
+            RunResult::Fault { kind, .. } => return Ok(Some(Stop::Fault(kind))),
+            _ => {},
+        }
+        if self.trace.len() == self.trace_len {
+            self.trace.pop_front();
+        }
+        if self.trace_len > 0 {
+            self.trace.push_back((pc, *inst));
+        }
+        self.steps += 1;
+        if let RunResult::Snd(v) = result {
+            writeln!(out, "snd {v}")?;
+        }
+        for (r, old) in self.watches.iter().zip(before) {
+            let new = self.vm.get_reg(*r);
+            if new != old {
+                return Ok(Some(Stop::Watch(*r, old, new)));
+            }
+        }
+        if result == RunResult::Halt {
+            return Ok(Some(Stop::Halted));
+        }
+        Ok(None)
+    }
+
+    fn report<W: Write>(&self, stop: Option<Stop>, out: &mut W) -> io::Result<()> {
+        match stop {
+            Some(Stop::Breakpoint) => writeln!(out, "breakpoint")?,
+            Some(Stop::Watch(r, old, new)) => writeln!(out, "watch {r}: {old} -> {new}")?,
+            Some(Stop::Blocked(r)) => writeln!(out, "blocked: rcv {r} with empty inbox (use send)")?,
+            Some(Stop::Fault(kind)) => writeln!(out, "fault: {kind} in {} arithmetic", self.vm.arithmetic.name())?,
+            Some(Stop::Halted) => {
+                return writeln!(out, "halted at pc {} after {} steps", self.vm.pc, self.steps);
+            },
+            None => {},
+        }
+        self.show_pc(out)
+    }
+
+    fn show_pc<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self.current() {
+            Some(inst) => writeln!(out, "pc {}: {inst}", self.vm.pc),
+            None => writeln!(out, "pc {}: halted", self.vm.pc),
+        }
+    }
+
+    fn step<W: Write>(&mut self, n: usize, out: &mut W) -> io::Result<()> {
+        let mut stop = None;
+        for _ in 0..n {
+            stop = self.exec_one(out)?;
+            if stop.is_some() {
+                break;
+            }
+        }
+        self.report(stop, out)
+    }
+
+    fn cont<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        // Always execute at least one instruction so continuing from a
+        // breakpoint doesn't stop on it again immediately.
+        let mut stop = self.exec_one(out)?;
+        while stop.is_none() {
+            if self.at_breakpoint() {
+                stop = Some(Stop::Breakpoint);
+            } else {
+                stop = self.exec_one(out)?;
+            }
+        }
+        self.report(stop, out)
+    }
+
+    fn regs<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let line: Vec<String> = self.vm.registers.iter().map(|(r, v)| format!("{r}={v}")).collect();
+        writeln!(out, "pc={} {}", self.vm.pc, line.join(" "))?;
+        if !self.vm.inbox.is_empty() {
+            writeln!(out, "inbox: {:?}", self.vm.inbox)?;
+        }
+        Ok(())
+    }
+
+    fn show_trace<W: Write>(&self, n: usize, out: &mut W) -> io::Result<()> {
+        let skip = self.trace.len().saturating_sub(n);
+        for (pc, inst) in self.trace.iter().skip(skip) {
+            writeln!(out, "{pc:>4}: {inst}")?;
+        }
+        Ok(())
+    }
+
+    fn list<W: Write>(&self, around: i64, out: &mut W) -> io::Result<()> {
+        let from = around.saturating_sub(5).max(0);
+        let to = around.saturating_add(5).min(self.vm.program.len() as i64 - 1);
+        for pc in from..=to {
+            let mark = if pc == self.vm.pc { "=>" } else { "  " };
+            let bp = if self.breakpoints.contains(&Breakpoint::Pc(pc)) { "*" } else { " " };
+            writeln!(out, "{mark}{bp}{pc:>4}: {}", self.vm.program[pc as usize])?;
+        }
+        Ok(())
+    }
+
+    fn info<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for b in &self.breakpoints {
+            match b {
+                Breakpoint::Pc(pc) => writeln!(out, "break pc {pc}")?,
+                Breakpoint::Op(op) => writeln!(out, "break {op}")?,
+            }
+        }
+        for r in &self.watches {
+            writeln!(out, "watch {r}")?;
+        }
+        Ok(())
+    }
+
+    // Returns false when the session should end.
+    pub fn command<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some(&cmd) = words.first() else { return Ok(true) };
+        let arg = words.get(1).copied();
+        let result = match (cmd, arg) {
+            ("s" | "step", None) => self.step(1, out),
+            ("s" | "step", Some(n)) => match n.parse::<usize>() {
+                Ok(n) => self.step(n, out),
+                Err(_) => writeln!(out, "bad step count: {n}"),
+            },
+            ("c" | "continue", _) => self.cont(out),
+            ("b" | "break", Some(a)) => match parse_breakpoint(a) {
+                Some(b) => {
+                    if !self.breakpoints.contains(&b) {
+                        self.breakpoints.push(b);
+                    }
+                    Ok(())
+                },
+                None => writeln!(out, "expected pc or opcode: {a}"),
+            },
+            ("d" | "delete", Some(a)) => match parse_breakpoint(a) {
+                Some(b) => {
+                    self.breakpoints.retain(|x| *x != b);
+                    Ok(())
+                },
+                None => writeln!(out, "expected pc or opcode: {a}"),
+            },
+            ("w" | "watch", Some(a)) => match resolve_register(&self.registers, a) {
+                Some(r) => {
+                    if !self.watches.contains(&r) {
+                        self.watches.push(r);
+                    }
+                    Ok(())
+                },
+                None => writeln!(out, "expected register: {a}"),
+            },
+            ("unwatch", Some(a)) => match resolve_register(&self.registers, a) {
+                Some(r) => {
+                    self.watches.retain(|x| *x != r);
+                    Ok(())
+                },
+                None => writeln!(out, "expected register: {a}"),
+            },
+            ("r" | "regs", _) => self.regs(out),
+            ("set", Some(a)) => {
+                match (resolve_register(&self.registers, a), words.get(2).and_then(|v| v.parse::<i64>().ok())) {
+                    (Some(r), Some(v)) => {
+                        self.vm.set_reg(r, v);
+                        Ok(())
+                    },
+                    _ => writeln!(out, "usage: set REG VALUE"),
+                }
+            },
+            ("send", Some(v)) => match v.parse::<i64>() {
+                Ok(v) => {
+                    self.vm.inbox.push_back(v);
+                    Ok(())
+                },
+                Err(_) => writeln!(out, "expected integer: {v}"),
+            },
+            ("arith", None) => writeln!(out, "arith {}", self.vm.arithmetic.name()),
+            ("arith", Some(a)) => match a.parse::<Arithmetic>() {
+                Ok(mode) => {
+                    self.vm.arithmetic = mode;
+                    Ok(())
+                },
+                Err(e) => writeln!(out, "{e}"),
+            },
+            ("t" | "trace", None) => self.show_trace(self.trace_len, out),
+            ("t" | "trace", Some(n)) => match n.parse::<usize>() {
+                Ok(n) => self.show_trace(n, out),
+                Err(_) => writeln!(out, "bad trace count: {n}"),
+            },
+            ("l" | "list", None) => self.list(self.vm.pc, out),
+            ("l" | "list", Some(pc)) => match pc.parse::<i64>() {
+                Ok(pc) => self.list(pc, out),
+                Err(_) => writeln!(out, "bad pc: {pc}"),
+            },
+            ("info", _) => self.info(out),
+            ("h" | "help", _) => writeln!(out, "{HELP}"),
+            ("q" | "quit", _) => return Ok(false),
+            _ => writeln!(out, "unknown command: {line} (try help)"),
+        };
+        result.map(|_| true)
+    }
+
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, out: &mut W) -> io::Result<()> {
+        self.show_pc(out)?;
+        let mut last = String::new();
+        for line in input.lines() {
+            let line = line?;
+            let line = if line.trim().is_empty() { last.clone() } else { line };
+            if !self.command(&line, out)? {
+                break;
+            }
+            out.flush()?;
+            last = line;
+        }
+        Ok(())
+    }
+}
+
+fn parse_breakpoint(s: &str) -> Option<Breakpoint> {
+    match s.parse::<i64>() {
+        Ok(pc) => Some(Breakpoint::Pc(pc)),
+        Err(_) => s.parse::<Opcode>().ok().map(Breakpoint::Op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{assemble, parse_program, Dialect};
+
+    #[test]
+    fn debugger_test() {
+        let lines: Vec<String> = "set a 3\nset b 0\nadd b a\nadd a -1\njgz a -2\nmul b 2"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let mut dbg = Debugger::new(VM::new(&prog), 3);
+        let script = "b 4\nc\n\nr\nd 4\nw b\nc\nunwatch b\nb mul\nc\nt 2\nc\n";
+        let mut out = Vec::new();
+        dbg.run(script.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, "\
+pc 0: set a 3
+breakpoint
+pc 4: jgz a -2
+breakpoint
+pc 4: jgz a -2
+pc=4 a=1 b=5
+watch b: 5 -> 6
+pc 3: add a -1
+breakpoint
+pc 5: mul b 2
+   3: add a -1
+   4: jgz a -2
+halted at pc 6 after 12 steps
+");
+
+        let lines: Vec<String> = "set a 9223372036854775807\nmul a 2\nadd a 1"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let mut dbg = Debugger::new(VM::new(&prog), 3);
+        let mut out = Vec::new();
+        dbg.run("c\narith saturating\narith\nc\nr\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+pc 0: set a 9223372036854775807
+fault: overflow in checked arithmetic
+pc 1: mul a 2
+arith saturating
+halted at pc 3 after 3 steps
+pc=3 a=9223372036854775807
+");
+
+        let asm = assemble(&["set total 4".to_string(), "add total 1".to_string()], Dialect::Duet).unwrap();
+        let mut dbg = Debugger::new(VM::new(&asm.program), 3).with_registers(asm.registers.clone());
+        let mut out = Vec::new();
+        dbg.run("set total 7\nw total\nc\nw a\nset b 0\nr\nl 9223372036854775807\nl -9223372036854775808\nl 6\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+pc 0: set a 4
+watch a: 7 -> 4
+pc 1: add a 1
+expected register: a
+pc=1 a=4 b=0
+=>    1: add a 1
+");
+    }
+}
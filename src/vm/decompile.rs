@@ -0,0 +1,456 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::vec::Vec;
+use crate::vm::isa::{Instruction, RI};
+
+// An expression built up by folding a `set r X` followed by arithmetic
+// on r, e.g. `set g d; mul g e; sub g b` becomes `d * e - b`.
+#[derive(Clone)]
+struct Expr {
+    text: String,
+    prec: u8,
+    // Both sides of the outermost subtraction, so `x - y` can be shown
+    // as the comparison `x != y`.
+    diff: Option<(String, String)>,
+}
+
+impl Expr {
+    fn atom(ri: RI) -> Self {
+        Self { text: ri.to_string(), prec: 3, diff: None }
+    }
+    fn apply(&self, op: char, operand: &str) -> Self {
+        let prec = if op == '+' || op == '-' { 1 } else { 2 };
+        let lhs = if self.prec < prec { format!("({})", self.text) } else { self.text.clone() };
+        let diff = (op == '-').then(|| (lhs.clone(), operand.to_string()));
+        Self { text: format!("{lhs} {op} {operand}"), prec, diff }
+    }
+}
+
+// The operator and operand an arithmetic instruction applies to its
+// destination, or None for anything else.
+fn arith(inst: &Instruction) -> Option<(char, char, String)> {
+    match *inst {
+        Instruction::Add(r, RI::Imm(n)) if n < 0 && n > i64::MIN => Some((r, '-', (-n).to_string())),
+        Instruction::Add(r, y) => Some((r, '+', y.to_string())),
+        Instruction::Sub(r, RI::Imm(n)) if n < 0 && n > i64::MIN => Some((r, '+', (-n).to_string())),
+        Instruction::Sub(r, y) => Some((r, '-', y.to_string())),
+        Instruction::Mul(r, y) => Some((r, '*', y.to_string())),
+        Instruction::Mod(r, y) => Some((r, '%', y.to_string())),
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Cond {
+    Always,
+    Never,
+    Cmp(String, &'static str, String),
+}
+
+impl Cond {
+    fn from_jump(x: RI, greater: bool, expr: Option<&Expr>) -> Self {
+        let op = if greater { ">" } else { "!=" };
+        match (x, expr) {
+            (RI::Imm(n), _) if greater => if n > 0 { Cond::Always } else { Cond::Never },
+            (RI::Imm(n), _) => if n != 0 { Cond::Always } else { Cond::Never },
+            (_, Some(Expr { diff: Some((l, r)), .. })) => Cond::Cmp(l.clone(), op, r.clone()),
+            (_, Some(e)) => Cond::Cmp(e.text.clone(), op, "0".into()),
+            (RI::Reg(r), None) => Cond::Cmp(r.to_string(), op, "0".into()),
+        }
+    }
+    fn negate(&self) -> Self {
+        match self {
+            Cond::Always => Cond::Never,
+            Cond::Never => Cond::Always,
+            Cond::Cmp(l, op, r) => {
+                let op = match *op {
+                    "!=" => "==",
+                    "==" => "!=",
+                    ">" => "<=",
+                    _ => ">",
+                };
+                Cond::Cmp(l.clone(), op, r.clone())
+            },
+        }
+    }
+    fn text(&self) -> String {
+        match self {
+            Cond::Always => "true".into(),
+            Cond::Never => "false".into(),
+            Cond::Cmp(l, op, r) => format!("{l} {op} {r}"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Target {
+    Pc(i64),
+    // jnz/jgz with a register offset
+    Computed(String),
+}
+
+#[derive(Clone)]
+enum Kind {
+    Plain(String),
+    Jump(Cond, Target),
+}
+
+// One or more instructions that read as a single statement
+#[derive(Clone)]
+struct Stmt {
+    first: usize,
+    last: usize,
+    kind: Kind,
+}
+
+fn reads(inst: &Instruction) -> Vec<char> {
+    let ris: Vec<RI> = match *inst {
+        Instruction::Snd(x) => vec![x],
+        Instruction::Set(_, y) => vec![y],
+        Instruction::Add(r, y)
+        | Instruction::Sub(r, y)
+        | Instruction::Mul(r, y)
+        | Instruction::Mod(r, y) => vec![RI::Reg(r), y],
+        Instruction::Rcv(_) => vec![],
+        Instruction::Composite(_, y) => vec![y],
+        Instruction::Jgz(x, y) | Instruction::Jnz(x, y) => vec![x, y],
+    };
+    ris.into_iter()
+        .filter_map(|ri| match ri {
+            RI::Reg(r) => Some(r),
+            RI::Imm(_) => None,
+        })
+        .collect()
+}
+
+fn jump_target(pc: usize, inst: &Instruction) -> Option<Target> {
+    match *inst {
+        Instruction::Jgz(_, RI::Imm(off)) | Instruction::Jnz(_, RI::Imm(off)) => {
+            // An offset that overflows saturates to a pc past the end,
+            // which is an exit like any other
+            Some(Target::Pc((pc as i64).saturating_add(off)))
+        },
+        Instruction::Jgz(_, RI::Reg(r)) | Instruction::Jnz(_, RI::Reg(r)) => {
+            Some(Target::Computed(format!("pc + {r}")))
+        },
+        _ => None,
+    }
+}
+
+struct Analysis<'a> {
+    program: &'a [Instruction],
+    block_starts: BTreeSet<usize>,
+    labels: BTreeMap<usize, usize>,
+    temps: BTreeSet<char>,
+    stmts: Vec<Stmt>,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(program: &'a [Instruction]) -> Self {
+        let len = program.len() as i64;
+        let mut block_starts = BTreeSet::from([0]);
+        let mut targets = BTreeSet::new();
+        for (pc, inst) in program.iter().enumerate() {
+            if let Some(t) = jump_target(pc, inst) {
+                block_starts.insert(pc + 1);
+                if let Target::Pc(t) = t {
+                    if (0..len).contains(&t) {
+                        targets.insert(t as usize);
+                    }
+                }
+            }
+        }
+        block_starts.extend(targets.iter().copied());
+        let labels = targets.iter().enumerate().map(|(n, pc)| (*pc, n + 1)).collect();
+        let mut a = Self { program, block_starts, labels, temps: BTreeSet::new(), stmts: Vec::new() };
+        a.temps = a.find_temps();
+        a.stmts = a.fold();
+        a
+    }
+
+    // Registers that are only ever read after being set earlier in the
+    // same block, so their value never matters outside it.
+    fn find_temps(&self) -> BTreeSet<char> {
+        let mut temps: BTreeSet<char> = self.program.iter().flat_map(reads).collect();
+        let mut set_here = BTreeSet::new();
+        for (pc, inst) in self.program.iter().enumerate() {
+            if self.block_starts.contains(&pc) {
+                set_here.clear();
+            }
+            for r in reads(inst) {
+                if !set_here.contains(&r) {
+                    temps.remove(&r);
+                }
+            }
+            if let Instruction::Set(r, _) = inst {
+                set_here.insert(*r);
+            }
+        }
+        temps
+    }
+
+    fn fold(&self) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+        let mut pc = 0;
+        while pc < self.program.len() {
+            let (stmt, next) = self.fold_at(pc);
+            stmts.push(stmt);
+            pc = next;
+        }
+        stmts
+    }
+
+    fn fold_at(&self, pc: usize) -> (Stmt, usize) {
+        let inst = &self.program[pc];
+        let stmt = |last, kind| Stmt { first: pc, last, kind };
+        if let Instruction::Set(r, x) = *inst {
+            let mut expr = Expr::atom(x);
+            let mut end = pc + 1;
+            while end < self.program.len() && !self.block_starts.contains(&end) {
+                match arith(&self.program[end]) {
+                    Some((d, op, operand)) if d == r && operand != r.to_string() => {
+                        expr = expr.apply(op, &operand);
+                        end += 1;
+                    },
+                    _ => break,
+                }
+            }
+            if self.temps.contains(&r) && end < self.program.len() && !self.block_starts.contains(&end) {
+                let jump = &self.program[end];
+                if let (Instruction::Jnz(RI::Reg(x), _) | Instruction::Jgz(RI::Reg(x), _), Some(target)) =
+                    (jump, jump_target(end, jump))
+                {
+                    if *x == r {
+                        let greater = matches!(jump, Instruction::Jgz(_, _));
+                        let cond = Cond::from_jump(RI::Reg(r), greater, Some(&expr));
+                        return (stmt(end, Kind::Jump(cond, target)), end + 1);
+                    }
+                }
+            }
+            return (stmt(end - 1, Kind::Plain(format!("{r} = {}", expr.text))), end);
+        }
+        let kind = match *inst {
+            Instruction::Snd(x) => Kind::Plain(format!("send({x})")),
+            Instruction::Rcv(r) => Kind::Plain(format!("{r} = receive()")),
+            Instruction::Composite(r, y) => Kind::Plain(format!("if (is_composite({y})) {r} = 0")),
+            Instruction::Jgz(x, _) | Instruction::Jnz(x, _) => {
+                let greater = matches!(inst, Instruction::Jgz(_, _));
+                Kind::Jump(Cond::from_jump(x, greater, None), jump_target(pc, inst).unwrap())
+            },
+            _ => {
+                let (r, op, operand) = arith(inst).unwrap();
+                Kind::Plain(format!("{r} {op}= {operand}"))
+            },
+        };
+        (stmt(pc, kind), pc + 1)
+    }
+
+    fn label(&self, pc: usize) -> String {
+        format!("[{}]", self.labels[&pc])
+    }
+
+    fn in_range(&self, t: i64) -> bool {
+        (0..self.program.len() as i64).contains(&t)
+    }
+
+    fn jump_text(&self, cond: &Cond, target: &Target) -> String {
+        let dest = match target {
+            Target::Pc(t) if self.in_range(*t) => format!("goto {}", self.label(*t as usize)),
+            Target::Pc(_) => "stop".into(),
+            Target::Computed(c) => format!("goto {c}"),
+        };
+        match cond {
+            Cond::Always => dest,
+            Cond::Never => "never".into(),
+            c => format!("if {} {dest}", c.text()),
+        }
+    }
+
+    fn listing(&self) -> String {
+        let mut out = String::new();
+        let mut comments: BTreeMap<usize, String> = BTreeMap::new();
+        for s in &self.stmts {
+            let text = match &s.kind {
+                Kind::Plain(t) => t.clone(),
+                Kind::Jump(c, t) => self.jump_text(c, t),
+            };
+            comments.insert(s.last, text);
+        }
+        for (pc, inst) in self.program.iter().enumerate() {
+            let label = self.labels.get(&pc).map(|_| self.label(pc)).unwrap_or_default();
+            let line = format!("{label:<4}{:<17}{}", inst.to_string(), comments.get(&pc).map_or("", |c| c));
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+        out
+    }
+
+    fn stmt_at(&self, pc: i64) -> Option<usize> {
+        self.stmts.iter().position(|s| s.first as i64 == pc)
+    }
+}
+
+struct Structurer<'a, 'b> {
+    a: &'b Analysis<'a>,
+    lines: Vec<(usize, Option<usize>, String)>,
+    gotos: BTreeSet<usize>,
+}
+
+impl Structurer<'_, '_> {
+    fn emit(&mut self, indent: usize, at: Option<usize>, text: String) {
+        self.lines.push((indent, at, text));
+    }
+
+    fn target_stmt(&self, s: usize) -> Option<usize> {
+        match &self.a.stmts[s].kind {
+            Kind::Jump(_, Target::Pc(t)) => self.a.stmt_at(*t),
+            _ => None,
+        }
+    }
+
+    fn cond(&self, s: usize) -> Option<&Cond> {
+        match &self.a.stmts[s].kind {
+            Kind::Jump(c, _) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn block(&mut self, start: usize, end: usize, indent: usize) {
+        let mut i = start;
+        while i < end {
+            // The furthest jump back to here closes a loop
+            if let Some(j) = (i..end).rev().find(|&j| self.target_stmt(j) == Some(i)) {
+                let cond = self.cond(j).unwrap().clone();
+                if cond == Cond::Always {
+                    self.emit(indent, Some(i), "while (true) {".into());
+                    self.block(i, j, indent + 1);
+                    self.emit(indent, Some(j), "}".into());
+                } else {
+                    self.emit(indent, Some(i), "do {".into());
+                    self.block(i, j, indent + 1);
+                    self.emit(indent, Some(j), format!("}} while ({});", cond.text()));
+                }
+                i = j + 1;
+                continue;
+            }
+            let stmt = self.a.stmts[i].clone();
+            let (cond, target) = match &stmt.kind {
+                Kind::Plain(text) => {
+                    self.emit(indent, Some(i), format!("{text};"));
+                    i += 1;
+                    continue;
+                },
+                Kind::Jump(c, t) => (c.clone(), t.clone()),
+            };
+            let t = self.target_stmt(i);
+            let conditional = matches!(cond, Cond::Cmp(_, _, _));
+            // `if (c) goto A; goto B; A: ...; B:` is a plain if
+            let skip = (i + 1 < end && t == Some(i + 2))
+                .then(|| (self.cond(i + 1), self.target_stmt(i + 1)))
+                .and_then(|(c2, t2)| (c2 == Some(&Cond::Always)).then_some(t2).flatten())
+                .filter(|&t2| conditional && t2 > i + 2 && t2 <= end);
+            if let Some(t2) = skip {
+                self.emit(indent, Some(i), format!("if ({}) {{", cond.text()));
+                self.block(i + 2, t2, indent + 1);
+                self.emit(indent, None, "}".into());
+                i = t2;
+            } else if let Some(t) = t.filter(|&t| conditional && t > i + 1 && t <= end) {
+                self.emit(indent, Some(i), format!("if ({}) {{", cond.negate().text()));
+                self.block(i + 1, t, indent + 1);
+                self.emit(indent, None, "}".into());
+                i = t;
+            } else {
+                let action = match (&target, t) {
+                    (Target::Pc(p), _) if !self.a.in_range(*p) => "halt".to_string(),
+                    (Target::Pc(_), Some(t)) => {
+                        self.gotos.insert(t);
+                        format!("goto {}", self.a.label(self.a.stmts[t].first))
+                    },
+                    (Target::Pc(p), None) => format!("goto {p}"),
+                    (Target::Computed(c), _) => format!("goto {c}"),
+                };
+                match cond {
+                    Cond::Always => self.emit(indent, Some(i), format!("{action};")),
+                    Cond::Never => {},
+                    c => self.emit(indent, Some(i), format!("if ({}) {action};", c.text())),
+                }
+                i += 1;
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let mut labelled = BTreeSet::new();
+        for (indent, at, text) in &self.lines {
+            if let Some(s) = at.filter(|s| self.gotos.contains(s) && labelled.insert(*s)) {
+                let label = self.a.label(self.a.stmts[s].first);
+                writeln!(out, "{}{label}:", "    ".repeat(*indent)).unwrap();
+            }
+            writeln!(out, "{}{text}", "    ".repeat(*indent)).unwrap();
+        }
+        out
+    }
+}
+
+// The program with jump targets labelled [1], [2], ... in pc order and
+// a comment giving the meaning of each statement, after folding chains
+// like `set g d; mul g e; sub g b; jnz g 2` into one condition.
+pub fn disassemble(program: &[Instruction]) -> String {
+    Analysis::new(program).listing()
+}
+
+// C-like pseudo-code: backward jumps become loops, forward jumps over a
+// block become ifs, and anything left over is a goto.
+pub fn decompile(program: &[Instruction]) -> String {
+    let analysis = Analysis::new(program);
+    let mut s = Structurer { a: &analysis, lines: Vec::new(), gotos: BTreeSet::new() };
+    s.block(0, analysis.stmts.len(), 0);
+    s.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    #[test]
+    fn decompile_test() {
+        let src = "set a 3\nset b 0\nset g a\nmul g 2\nsub g 4\njnz g 2\nsub b -1\n\
+            sub a 1\njnz a -6\njnz b 2\njnz 1 2\nset h b";
+        let lines: Vec<String> = src.lines().map(|l| l.trim().to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        assert_eq!(disassemble(&prog), &"
+    set a 3          a = 3
+    set b 0          b = 0
+[1] set g a
+    mul g 2
+    sub g 4
+    jnz g 2          if a * 2 != 4 goto [2]
+    sub b -1         b += 1
+[2] sub a 1          a -= 1
+    jnz a -6         if a != 0 goto [1]
+    jnz b 2          if b != 0 goto [3]
+    jnz 1 2          stop
+[3] set h b          h = b
+"[1..]);
+        assert_eq!(decompile(&prog), "\
+a = 3;
+b = 0;
+do {
+    if (a * 2 == 4) {
+        b += 1;
+    }
+    a -= 1;
+} while (a != 0);
+if (b == 0) {
+    halt;
+}
+h = b;
+");
+
+        let lines: Vec<String> = "set b 1\njnz 1 9223372036854775807".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        assert!(disassemble(&prog).ends_with("stop\n"));
+        assert_eq!(decompile(&prog), "b = 1;\nhalt;\n");
+    }
+}
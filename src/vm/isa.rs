@@ -0,0 +1,217 @@
+use std::fmt;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::parse::{next_token, parse_token, ParseError};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RI {
+    Reg(char),
+    Imm(i64),
+}
+impl FromStr for RI {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(f @ 'a' ..= 'z'), None) => Ok(RI::Reg(f)),
+            _ => parse_token(s, s, "register or integer").map(RI::Imm),
+        }
+    }
+}
+
+impl fmt::Display for RI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RI::Reg(r) => write!(f, "{r}"),
+            RI::Imm(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+fn next_ri<'a>(s: &str, itr: &mut impl Iterator<Item=&'a str>) -> Result<RI, ParseError> {
+    let t = next_token(s, itr, "register or integer")?;
+    t.parse::<RI>().map_err(|e| e.within(s, t))
+}
+
+fn next_reg<'a>(s: &str, itr: &mut impl Iterator<Item=&'a str>) -> Result<char, ParseError> {
+    let t = next_token(s, itr, "register")?;
+    match t.parse::<RI>() {
+        Ok(RI::Reg(r)) => Ok(r),
+        _ => Err(ParseError::new(s, t, "register")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Opcode {
+    Snd,
+    Set,
+    Add,
+    Sub,
+    Mul,
+    Mod,
+    Rcv,
+    Jgz,
+    Jnz,
+    // Only produced by the optimizer; no dialect parses it
+    Composite,
+}
+
+impl Opcode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Opcode::Snd => "snd",
+            Opcode::Set => "set",
+            Opcode::Add => "add",
+            Opcode::Sub => "sub",
+            Opcode::Mul => "mul",
+            Opcode::Mod => "mod",
+            Opcode::Rcv => "rcv",
+            Opcode::Jgz => "jgz",
+            Opcode::Jnz => "jnz",
+            Opcode::Composite => "composite",
+        }
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "snd" => Ok(Opcode::Snd),
+            "set" => Ok(Opcode::Set),
+            "add" => Ok(Opcode::Add),
+            "sub" => Ok(Opcode::Sub),
+            "mul" => Ok(Opcode::Mul),
+            "mod" => Ok(Opcode::Mod),
+            "rcv" => Ok(Opcode::Rcv),
+            "jgz" => Ok(Opcode::Jgz),
+            "jnz" => Ok(Opcode::Jnz),
+            "composite" => Ok(Opcode::Composite),
+            _ => Err(ParseError::new(s, s, "opcode")),
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Which opcodes a program may use: day18's duet programs or day23's
+// coprocessor programs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dialect {
+    Duet,
+    Coprocessor,
+}
+
+impl Dialect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::Duet => "duet",
+            Dialect::Coprocessor => "coprocessor",
+        }
+    }
+    pub fn opcodes(&self) -> &'static [Opcode] {
+        match self {
+            Dialect::Duet => &[
+                Opcode::Snd, Opcode::Set, Opcode::Add, Opcode::Mul,
+                Opcode::Mod, Opcode::Rcv, Opcode::Jgz,
+            ],
+            Dialect::Coprocessor => &[Opcode::Set, Opcode::Sub, Opcode::Mul, Opcode::Jnz],
+        }
+    }
+    pub fn supports(&self, op: Opcode) -> bool {
+        self.opcodes().contains(&op)
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "duet" => Ok(Dialect::Duet),
+            "coprocessor" => Ok(Dialect::Coprocessor),
+            _ => Err(format!("unknown dialect: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Instruction {
+    Snd(RI),
+    Set(char, RI),
+    Add(char, RI),
+    Sub(char, RI),
+    Mul(char, RI),
+    Mod(char, RI),
+    Rcv(char),
+    Jgz(RI, RI),
+    Jnz(RI, RI),
+    // Clears the register when the value has a factorization a * b with
+    // both factors at least 2
+    Composite(char, RI),
+}
+
+impl Instruction {
+    pub fn parse(s: &str, dialect: Dialect) -> Result<Self, ParseError> {
+        let mut itr = s.split(' ');
+        let token = next_token(s, &mut itr, "opcode")?;
+        let opcode = match token.parse::<Opcode>() {
+            Ok(op) if dialect.supports(op) => op,
+            _ => return Err(ParseError::new(s, token, &format!("{} opcode", dialect.name()))),
+        };
+        match opcode {
+            Opcode::Snd => Ok(Instruction::Snd(next_ri(s, &mut itr)?)),
+            Opcode::Set => Ok(Instruction::Set(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Add => Ok(Instruction::Add(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Sub => Ok(Instruction::Sub(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Mul => Ok(Instruction::Mul(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Mod => Ok(Instruction::Mod(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Rcv => Ok(Instruction::Rcv(next_reg(s, &mut itr)?)),
+            Opcode::Jgz => Ok(Instruction::Jgz(next_ri(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Jnz => Ok(Instruction::Jnz(next_ri(s, &mut itr)?, next_ri(s, &mut itr)?)),
+            Opcode::Composite => Ok(Instruction::Composite(next_reg(s, &mut itr)?, next_ri(s, &mut itr)?)),
+        }
+    }
+    pub fn opcode(&self) -> Opcode {
+        match self {
+            Instruction::Snd(_) => Opcode::Snd,
+            Instruction::Set(_, _) => Opcode::Set,
+            Instruction::Add(_, _) => Opcode::Add,
+            Instruction::Sub(_, _) => Opcode::Sub,
+            Instruction::Mul(_, _) => Opcode::Mul,
+            Instruction::Mod(_, _) => Opcode::Mod,
+            Instruction::Rcv(_) => Opcode::Rcv,
+            Instruction::Jgz(_, _) => Opcode::Jgz,
+            Instruction::Jnz(_, _) => Opcode::Jnz,
+            Instruction::Composite(_, _) => Opcode::Composite,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = self.opcode();
+        match self {
+            Instruction::Snd(x) => write!(f, "{op} {x}"),
+            Instruction::Rcv(x) => write!(f, "{op} {x}"),
+            Instruction::Set(x, y)
+            | Instruction::Add(x, y)
+            | Instruction::Sub(x, y)
+            | Instruction::Mul(x, y)
+            | Instruction::Mod(x, y)
+            | Instruction::Composite(x, y) => write!(f, "{op} {x} {y}"),
+            Instruction::Jgz(x, y)
+            | Instruction::Jnz(x, y) => write!(f, "{op} {x} {y}"),
+        }
+    }
+}
+
+pub fn parse_program(lines: &[String], dialect: Dialect) -> Result<Vec<Instruction>, ParseError> {
+    lines.iter()
+        .enumerate()
+        .map(|(idx, l)| Instruction::parse(l, dialect).map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
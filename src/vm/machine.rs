@@ -0,0 +1,298 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use ya_advent_lib::math::is_prime;
+use crate::vm::isa::{Instruction, Opcode, RI};
+
+// One slot per register. The parser only accepts 'a'..='z' as register
+// names, so a name's slot is fixed before the program ever runs and
+// execution never has to hash or insert. Alongside, one bit per slot
+// records whether the register was ever written.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Registers([i64; 26], u32);
+
+fn slot(r: char) -> usize {
+    r as usize - 'a' as usize
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn get(&self, r: char) -> i64 {
+        self.0[slot(r)]
+    }
+    pub fn get_mut(&mut self, r: char) -> &mut i64 {
+        self.1 |= 1 << slot(r);
+        &mut self.0[slot(r)]
+    }
+    // Registers that have been written, even if back to zero, in name
+    // order
+    pub fn iter(&self) -> impl Iterator<Item = (char, i64)> + '_ {
+        ('a'..='z').zip(self.0).enumerate()
+            .filter(|(i, _)| self.1 & (1 << i) != 0)
+            .map(|(_, rv)| rv)
+    }
+}
+
+// How add, sub, mul and mod treat results that don't fit in an i64.
+// Taking a value mod zero faults in every mode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Arithmetic {
+    #[default]
+    Checked,
+    Wrapping,
+    Saturating,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FaultKind {
+    Overflow,
+    DivideByZero,
+}
+
+impl Arithmetic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Arithmetic::Checked => "checked",
+            Arithmetic::Wrapping => "wrapping",
+            Arithmetic::Saturating => "saturating",
+        }
+    }
+    fn apply(self, op: Opcode, a: i64, b: i64) -> Result<i64, FaultKind> {
+        if op == Opcode::Mod && b == 0 {
+            return Err(FaultKind::DivideByZero);
+        }
+        match self {
+            Arithmetic::Checked => match op {
+                Opcode::Add => a.checked_add(b),
+                Opcode::Sub => a.checked_sub(b),
+                Opcode::Mul => a.checked_mul(b),
+                _ => a.checked_rem(b),
+            }.ok_or(FaultKind::Overflow),
+            Arithmetic::Wrapping => Ok(match op {
+                Opcode::Add => a.wrapping_add(b),
+                Opcode::Sub => a.wrapping_sub(b),
+                Opcode::Mul => a.wrapping_mul(b),
+                _ => a.wrapping_rem(b),
+            }),
+            // The only overflowing remainder is i64::MIN % -1, and its
+            // true value 0 is what wrapping_rem gives
+            Arithmetic::Saturating => Ok(match op {
+                Opcode::Add => a.saturating_add(b),
+                Opcode::Sub => a.saturating_sub(b),
+                Opcode::Mul => a.saturating_mul(b),
+                _ => a.wrapping_rem(b),
+            }),
+        }
+    }
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "checked" => Ok(Arithmetic::Checked),
+            "wrapping" => Ok(Arithmetic::Wrapping),
+            "saturating" => Ok(Arithmetic::Saturating),
+            _ => Err(format!("unknown arithmetic mode: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultKind::Overflow => write!(f, "overflow"),
+            FaultKind::DivideByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RunResult {
+    Ok,
+    Halt,
+    // The trap asked to stop before executing the instruction at pc
+    Break,
+    Snd(i64),
+    // rcv found the inbox empty; pc stays on the rcv until a value arrives
+    Blocked(char),
+    // An arithmetic instruction failed; pc stays on it
+    Fault { pc: i64, kind: FaultKind },
+}
+
+// Called before each instruction executes; returning true stops the VM
+// with RunResult::Break.
+pub type TrapFunc<'a> = &'a mut dyn FnMut(&Instruction, &Registers) -> bool;
+
+pub struct VM<'a> {
+    pub registers: Registers,
+    pub program: &'a [Instruction],
+    pub pc: i64,
+    pub inbox: VecDeque<i64>,
+    pub sent: usize,
+    pub arithmetic: Arithmetic,
+    trap: Option<TrapFunc<'a>>,
+}
+
+impl<'a> VM<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        Self {
+            registers: Registers::new(),
+            program,
+            pc: 0,
+            inbox: VecDeque::new(),
+            sent: 0,
+            arithmetic: Arithmetic::default(),
+            trap: None,
+        }
+    }
+    pub fn set_trap(&mut self, trap: TrapFunc<'a>) {
+        self.trap = Some(trap);
+    }
+    pub fn is_halted(&self) -> bool {
+        self.pc < 0 || self.pc >= self.program.len() as i64
+    }
+    pub fn step(&mut self) -> RunResult {
+        if self.is_halted() {
+            return RunResult::Halt;
+        }
+        let inst = &self.program[self.pc as usize];
+        if let Some(trap) = self.trap.as_mut() {
+            if (*trap)(inst, &self.registers) {
+                return RunResult::Break;
+            }
+        }
+        match inst {
+            Instruction::Snd(x) => {
+                self.pc += 1;
+                self.sent += 1;
+                return RunResult::Snd(self.resolve(*x));
+            },
+            Instruction::Set(x, y) => {
+                *self.registers.get_mut(*x) = self.resolve(*y);
+            },
+            Instruction::Add(x, y)
+            | Instruction::Sub(x, y)
+            | Instruction::Mul(x, y)
+            | Instruction::Mod(x, y) => {
+                match self.arithmetic.apply(inst.opcode(), self.get_reg(*x), self.resolve(*y)) {
+                    Ok(v) => self.set_reg(*x, v),
+                    Err(kind) => return RunResult::Fault { pc: self.pc, kind },
+                }
+            },
+            Instruction::Rcv(x) => {
+                match self.inbox.pop_front() {
+                    Some(val) => self.set_reg(*x, val),
+                    None => return RunResult::Blocked(*x),
+                }
+            },
+            Instruction::Jgz(x, y) => {
+                if self.resolve(*x) > 0 {
+                    self.jump(self.resolve(*y));
+                }
+            },
+            Instruction::Jnz(x, y) => {
+                if self.resolve(*x) != 0 {
+                    self.jump(self.resolve(*y));
+                }
+            },
+            Instruction::Composite(x, y) => {
+                let n = self.resolve(*y);
+                if n > 3 && !is_prime(n as u64) {
+                    self.set_reg(*x, 0);
+                }
+            },
+        }
+        self.pc += 1;
+        if self.is_halted() {
+            RunResult::Halt
+        }
+        else {
+            RunResult::Ok
+        }
+    }
+    // Relative to the current instruction; anything far out of range
+    // just halts.
+    fn jump(&mut self, offset: i64) {
+        self.pc = self.pc.saturating_add(offset).saturating_sub(1);
+    }
+    pub fn resolve(&self, ri: RI) -> i64 {
+        match ri {
+            RI::Imm(x) => x,
+            RI::Reg(r) => self.get_reg(r),
+        }
+    }
+    pub fn get_reg(&self, r: char) -> i64 {
+        self.registers.get(r)
+    }
+    pub fn set_reg(&mut self, r: char, v: i64) {
+        *self.registers.get_mut(r) = v;
+    }
+    // Runs until anything other than RunResult::Ok happens.
+    pub fn run(&mut self) -> RunResult {
+        loop {
+            let r = self.step();
+            match r {
+                RunResult::Ok => {},
+                _ => return r,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    fn program(src: &str, dialect: Dialect) -> Vec<Instruction> {
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        parse_program(&lines, dialect).unwrap()
+    }
+
+    #[test]
+    fn vm_test() {
+        let prog = program("set a 3\nsnd a\nrcv b\nadd b a\njgz b -2", Dialect::Duet);
+        let mut vm = VM::new(&prog);
+        assert_eq!(vm.run(), RunResult::Snd(3));
+        assert_eq!(vm.run(), RunResult::Blocked('b'));
+        assert_eq!(vm.pc, 2);
+        vm.inbox.push_back(-3);
+        assert_eq!(vm.run(), RunResult::Halt);
+        assert_eq!((vm.get_reg('b'), vm.sent), (0, 1));
+
+        let prog = program("set b 4\nmul b b\nsub b 1\njnz b -1", Dialect::Coprocessor);
+        let mut muls = 0;
+        let mut trap = |inst: &Instruction, _: &Registers| {
+            if let Instruction::Mul(_, _) = inst {
+                muls += 1;
+            }
+            matches!(inst, Instruction::Jnz(RI::Reg('b'), _))
+        };
+        let mut vm = VM::new(&prog);
+        vm.set_trap(&mut trap);
+        assert_eq!(vm.run(), RunResult::Break);
+        assert_eq!((vm.pc, vm.get_reg('b')), (3, 15));
+        drop(vm);
+        assert_eq!(muls, 1);
+
+        let lines = vec!["snd a".to_string()];
+        let err = parse_program(&lines, Dialect::Coprocessor).err().unwrap();
+        assert_eq!(err.to_string(), "line 1, column 1: expected coprocessor opcode, found \"snd\"");
+
+        let prog = program("set a 9223372036854775807\nadd a 1\nmod a b", Dialect::Duet);
+        let mut vm = VM::new(&prog);
+        assert_eq!(vm.run(), RunResult::Fault { pc: 1, kind: FaultKind::Overflow });
+        assert_eq!((vm.pc, vm.get_reg('a')), (1, i64::MAX));
+        vm.arithmetic = Arithmetic::Saturating;
+        assert_eq!(vm.run(), RunResult::Fault { pc: 2, kind: FaultKind::DivideByZero });
+        assert_eq!(vm.get_reg('a'), i64::MAX);
+        let mut vm = VM::new(&prog);
+        vm.arithmetic = "wrapping".parse().unwrap();
+        vm.step();
+        vm.step();
+        assert_eq!(vm.get_reg('a'), i64::MIN);
+    }
+}
@@ -0,0 +1,25 @@
+mod asm;
+mod cfg;
+mod debugger;
+mod decompile;
+mod isa;
+mod machine;
+mod network;
+mod optimize;
+mod symbolic;
+mod profile;
+mod threaded;
+mod trace;
+
+pub use asm::{assemble, Assembly};
+pub use cfg::{Block, Cfg, Edge, Target};
+pub use debugger::{Breakpoint, Debugger};
+pub use decompile::{decompile, disassemble};
+pub use isa::{parse_program, Dialect, Instruction, Opcode, RI};
+pub use machine::{Arithmetic, FaultKind, Registers, RunResult, TrapFunc, VM};
+pub use network::{Blocked, Channel, ChannelStats, Faulted, Network, Outcome, ProgramState, Report, Topology, Wait};
+pub use optimize::{optimize, Rewrite};
+pub use profile::{annotate, profile, HotLoop, Profile};
+pub use symbolic::{symbolic, Branch, Condition, Poly, Test};
+pub use threaded::run_threaded;
+pub use trace::{diff as diff_traces, record, replay, Divergence, End, Entry, Event, Io, Trace};
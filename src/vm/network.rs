@@ -0,0 +1,350 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::vm::isa::Instruction;
+use crate::vm::machine::{FaultKind, RunResult, VM};
+
+// Who hears each program's snd
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Topology {
+    // i sends to i + 1, wrapping; with two programs this is day18's pair
+    Ring,
+    // i sends to every other program
+    Broadcast,
+    // Explicit (from, to) links
+    Routes(Vec<(usize, usize)>),
+}
+
+impl Topology {
+    pub fn links(&self, n: usize) -> Result<Vec<(usize, usize)>, String> {
+        match self {
+            Topology::Ring if n < 2 => Err("a ring needs at least 2 programs".into()),
+            Topology::Ring => Ok((0..n).map(|i| (i, (i + 1) % n)).collect()),
+            Topology::Broadcast => Ok((0..n)
+                .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+                .collect()),
+            Topology::Routes(routes) => {
+                if let Some((f, t)) = routes.iter().find(|(f, t)| *f >= n || *t >= n) {
+                    return Err(format!("route {f}-{t} is outside programs 0..{n}"));
+                }
+                Ok(routes.clone())
+            },
+        }
+    }
+}
+
+// "ring", "broadcast", or routes like "0-1,1-2,2-0"
+impl FromStr for Topology {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ring" => Ok(Topology::Ring),
+            "broadcast" => Ok(Topology::Broadcast),
+            _ => s.split(',')
+                .map(|route| {
+                    route.split_once('-')
+                        .and_then(|(f, t)| Some((f.parse().ok()?, t.parse().ok()?)))
+                        .ok_or_else(|| format!("bad route: {route} (expected FROM-TO)"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Topology::Routes),
+        }
+    }
+}
+
+pub struct Channel {
+    pub from: usize,
+    pub to: usize,
+    pub queue: VecDeque<i64>,
+    pub sent: usize,
+    pub received: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Wait {
+    // rcv into the register, with every incoming channel empty
+    Recv(char, Vec<usize>),
+    // snd into a full channel (only with bounded channels)
+    Send(usize),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Blocked {
+    pub program: usize,
+    pub pc: i64,
+    // Channels are indices into Report::channels / Network::channels
+    pub wait: Wait,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Faulted {
+    pub program: usize,
+    pub pc: i64,
+    pub kind: FaultKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    AllHalted,
+    // Every program is halted or stuck; lists the stuck ones
+    Deadlock(Vec<Blocked>),
+    // Some programs stopped on an arithmetic fault; the rest ran until
+    // they halted or got stuck
+    Fault(Vec<Faulted>),
+    // Reported before run() has finished, or after stepping the VMs by
+    // hand: some program could still move
+    Running,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramState {
+    pub pc: i64,
+    pub halted: bool,
+    pub sent: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChannelStats {
+    pub from: usize,
+    pub to: usize,
+    pub sent: usize,
+    pub received: usize,
+}
+
+// The end state of a network run, from either scheduler
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    pub outcome: Outcome,
+    pub programs: Vec<ProgramState>,
+    pub channels: Vec<ChannelStats>,
+}
+
+// Any number of duet programs, each with its own VM and p register set
+// to its id, connected by one queue per link.
+pub struct Network<'a> {
+    pub vms: Vec<VM<'a>>,
+    pub channels: Vec<Channel>,
+    // Where each program's next rcv starts looking, so a busy channel
+    // can't starve the others
+    cursors: Vec<usize>,
+    faults: Vec<Option<FaultKind>>,
+}
+
+impl<'a> Network<'a> {
+    pub fn new(program: &'a [Instruction], n: usize, topology: &Topology) -> Result<Self, String> {
+        let channels = topology.links(n)?
+            .into_iter()
+            .map(|(from, to)| Channel { from, to, queue: VecDeque::new(), sent: 0, received: 0 })
+            .collect();
+        let vms = (0..n)
+            .map(|id| {
+                let mut vm = VM::new(program);
+                vm.set_reg('p', id as i64);
+                vm
+            })
+            .collect();
+        Ok(Self { vms, channels, cursors: vec![0; n], faults: vec![None; n] })
+    }
+
+    fn incoming(&self, id: usize) -> Vec<usize> {
+        (0..self.channels.len()).filter(|c| self.channels[*c].to == id).collect()
+    }
+
+    // Moves one value from an incoming channel into the program's inbox.
+    fn deliver(&mut self, id: usize) -> bool {
+        let incoming = self.incoming(id);
+        for k in 0..incoming.len() {
+            let c = incoming[(self.cursors[id] + k) % incoming.len()];
+            if let Some(v) = self.channels[c].queue.pop_front() {
+                self.channels[c].received += 1;
+                self.vms[id].inbox.push_back(v);
+                self.cursors[id] = (self.cursors[id] + k + 1) % incoming.len();
+                return true;
+            }
+        }
+        false
+    }
+
+    // Runs program `id` until it halts or runs out of input, returning
+    // whether anything was sent or received.
+    fn run_one(&mut self, id: usize) -> bool {
+        let mut progress = false;
+        loop {
+            match self.vms[id].run() {
+                RunResult::Snd(v) => {
+                    for ch in self.channels.iter_mut().filter(|ch| ch.from == id) {
+                        ch.queue.push_back(v);
+                        ch.sent += 1;
+                    }
+                    progress = true;
+                },
+                RunResult::Blocked(_) => {
+                    if !self.deliver(id) {
+                        return progress;
+                    }
+                    progress = true;
+                },
+                RunResult::Fault { kind, .. } => {
+                    self.faults[id] = Some(kind);
+                    return progress;
+                },
+                _ => return progress,
+            }
+        }
+    }
+
+    pub fn run(&mut self) -> Outcome {
+        loop {
+            let mut progress = false;
+            for id in 0..self.vms.len() {
+                progress |= self.run_one(id);
+            }
+            if !progress {
+                return self.outcome();
+            }
+        }
+    }
+
+    fn outcome(&self) -> Outcome {
+        let faulted: Vec<Faulted> = self.faults.iter()
+            .enumerate()
+            .filter_map(|(id, f)| f.map(|kind| Faulted { program: id, pc: self.vms[id].pc, kind }))
+            .collect();
+        if !faulted.is_empty() {
+            return Outcome::Fault(faulted);
+        }
+        // A program is only stuck at a rcv with nothing left to receive
+        let blocked: Option<Vec<Blocked>> = self.vms.iter()
+            .enumerate()
+            .filter(|(_, vm)| !vm.is_halted())
+            .map(|(id, vm)| {
+                let incoming = self.incoming(id);
+                match vm.program[vm.pc as usize] {
+                    Instruction::Rcv(r) if vm.inbox.is_empty()
+                        && incoming.iter().all(|c| self.channels[*c].queue.is_empty()) => {
+                        Some(Blocked { program: id, pc: vm.pc, wait: Wait::Recv(r, incoming) })
+                    },
+                    _ => None,
+                }
+            })
+            .collect();
+        let Some(blocked) = blocked else {
+            return Outcome::Running;
+        };
+        if blocked.is_empty() {
+            Outcome::AllHalted
+        } else {
+            Outcome::Deadlock(blocked)
+        }
+    }
+}
+
+impl Network<'_> {
+    pub fn report(&self) -> Report {
+        Report {
+            outcome: self.outcome(),
+            programs: self.vms.iter()
+                .map(|vm| ProgramState { pc: vm.pc, halted: vm.is_halted(), sent: vm.sent })
+                .collect(),
+            channels: self.channels.iter()
+                .map(|ch| ChannelStats { from: ch.from, to: ch.to, sent: ch.sent, received: ch.received })
+                .collect(),
+        }
+    }
+}
+
+impl Report {
+    fn link(&self, c: usize) -> String {
+        format!("{}->{}", self.channels[c].from, self.channels[c].to)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            Outcome::AllHalted => writeln!(f, "all programs halted")?,
+            Outcome::Running => writeln!(f, "still running")?,
+            Outcome::Deadlock(blocked) => {
+                let waits: Vec<String> = blocked.iter()
+                    .map(|b| {
+                        let what = match &b.wait {
+                            Wait::Recv(reg, chans) if chans.is_empty() => format!("rcv {reg}) waiting on nothing"),
+                            Wait::Recv(reg, chans) => {
+                                let chans: Vec<String> = chans.iter().map(|c| self.link(*c)).collect();
+                                format!("rcv {reg}) waiting on {}", chans.join(", "))
+                            },
+                            Wait::Send(c) => format!("snd) waiting on full {}", self.link(*c)),
+                        };
+                        format!("program {} at pc {} ({what}", b.program, b.pc)
+                    })
+                    .collect();
+                writeln!(f, "deadlock: {}", waits.join("; "))?;
+            },
+            Outcome::Fault(faulted) => {
+                let faults: Vec<String> = faulted.iter()
+                    .map(|p| format!("program {} at pc {} ({})", p.program, p.pc, p.kind))
+                    .collect();
+                writeln!(f, "fault: {}", faults.join("; "))?;
+            },
+        }
+        for (id, p) in self.programs.iter().enumerate() {
+            let state = if p.halted { "halted".into() } else { format!("pc {}", p.pc) };
+            writeln!(f, "program {id}: {state}, sent {}", p.sent)?;
+        }
+        for ch in &self.channels {
+            writeln!(
+                f,
+                "channel {}->{}: sent {}, received {}, queued {}",
+                ch.from, ch.to, ch.sent, ch.received, ch.sent - ch.received,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    #[test]
+    fn network_test() {
+        // Each program passes on what it receives, plus its own id, until
+        // it has sent three values, then waits for two more.
+        let src = "snd p\nset c 2\nrcv a\nadd a p\nsnd a\nadd c -1\njgz c -4\nrcv b\nrcv b";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+
+        let mut net = Network::new(&prog, 3, &Topology::Ring).unwrap();
+        assert_eq!(net.report().outcome, Outcome::Running);
+        net.vms[1].run();
+        assert!(net.report().to_string().starts_with("still running\n"));
+        let mut net = Network::new(&prog, 3, &Topology::Ring).unwrap();
+        let outcome = net.run();
+        let Outcome::Deadlock(blocked) = &outcome else { panic!() };
+        assert_eq!(blocked.len(), 3);
+        assert_eq!((blocked[0].pc, &blocked[0].wait), (8, &Wait::Recv('b', vec![2])));
+        assert_eq!(net.vms.iter().map(|vm| vm.sent).collect::<Vec<_>>(), vec![3, 3, 3]);
+        assert_eq!(net.channels.iter().map(|ch| ch.received).collect::<Vec<_>>(), vec![3, 3, 3]);
+        assert!(net.report().to_string().starts_with("deadlock: program 0 at pc 8 (rcv b) waiting on 2->0;"));
+
+        let mut net = Network::new(&prog, 3, &Topology::Broadcast).unwrap();
+        net.run();
+        assert_eq!(net.channels.len(), 6);
+        assert_eq!(net.channels.iter().map(|ch| ch.queue.len()).sum::<usize>(), 6);
+
+        let routes = "0-1,1-2".parse::<Topology>().unwrap();
+        assert_eq!(routes, Topology::Routes(vec![(0, 1), (1, 2)]));
+        assert!(Network::new(&prog, 2, &routes).is_err());
+        assert!("0-x".parse::<Topology>().is_err());
+
+        // Program 1 divides by the 0 it receives
+        let lines: Vec<String> = "snd p\nrcv a\nmod p a".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let mut net = Network::new(&prog, 2, &Topology::Ring).unwrap();
+        let expected = Faulted { program: 1, pc: 2, kind: FaultKind::DivideByZero };
+        assert_eq!(net.run(), Outcome::Fault(vec![expected]));
+        assert!(net.report().to_string().starts_with("fault: program 1 at pc 2 (division by zero)\nprogram 0: halted"));
+    }
+}
@@ -0,0 +1,175 @@
+use std::vec::Vec;
+use crate::vm::isa::{Instruction, RI};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rewrite {
+    pub pc: usize,
+    pub len: usize,
+    pub description: String,
+}
+
+const COMPOSITE_LOOP_LEN: usize = 15;
+
+// Recognizes the brute-force factor search
+//
+//   set d 2
+//   set e 2        <-----+
+//   set g d        <-+   |
+//   mul g e          |   |
+//   sub g n          |   |
+//   jnz g 2          |   |   if d * e == n
+//   set f 0          |   |       f = 0
+//   sub e -1         |   |
+//   set g e          |   |
+//   sub g n          |   |
+//   jnz g -8       --+   |   until e == n
+//   sub d -1             |
+//   set g d              |
+//   sub g n              |
+//   jnz g -13      ------+   until d == n
+//
+// with any choice of distinct registers, returning (f, n, d, e, g).
+fn composite_loop(window: &[Instruction]) -> Option<(char, RI, char, char, char)> {
+    use Instruction::*;
+    use RI::{Imm, Reg};
+    let [
+        Set(d, Imm(2)), Set(e, Imm(2)),
+        Set(g1, Reg(d1)), Mul(g2, Reg(e1)), Sub(g3, n1), Jnz(Reg(g4), Imm(2)),
+        Set(f, Imm(0)),
+        Sub(e2, Imm(-1)), Set(g5, Reg(e3)), Sub(g6, n2), Jnz(Reg(g7), Imm(-8)),
+        Sub(d2, Imm(-1)), Set(g8, Reg(d3)), Sub(g9, n3), Jnz(Reg(g10), Imm(-13)),
+    ] = *window else {
+        return None;
+    };
+    let g = g1;
+    let same = [d1, d2, d3].iter().all(|r| *r == d)
+        && [e1, e2, e3].iter().all(|r| *r == e)
+        && [g2, g3, g4, g5, g6, g7, g8, g9, g10].iter().all(|r| *r == g)
+        && n1 == n2 && n1 == n3;
+    let mut regs = vec![d, e, g, f];
+    if let Reg(n) = n1 {
+        regs.push(n);
+    }
+    regs.sort();
+    regs.dedup();
+    let distinct = regs.len() == if matches!(n1, Reg(_)) { 5 } else { 4 };
+    (same && distinct).then_some((f, n1, d, e, g))
+}
+
+fn jumps_into(program: &[Instruction], from: usize, to: usize) -> bool {
+    program.iter().enumerate().any(|(pc, inst)| {
+        // An offset that overflows leaves the program, not the loop
+        let target = match inst {
+            Instruction::Jgz(_, RI::Imm(off)) | Instruction::Jnz(_, RI::Imm(off)) => match (pc as i64).checked_add(*off) {
+                Some(t) => t,
+                None => return false,
+            },
+            _ => return false,
+        };
+        (pc < from || pc >= to) && target > from as i64 && target < to as i64
+    })
+}
+
+fn has_computed_jump(program: &[Instruction]) -> bool {
+    program.iter().any(|inst| {
+        matches!(inst, Instruction::Jgz(_, RI::Reg(_)) | Instruction::Jnz(_, RI::Reg(_)))
+    })
+}
+
+// Replaces known slow loops with native instructions. The program keeps
+// its length so every relative jump still lands where it did; the tail
+// of a replaced loop becomes dead code that a jump skips over.
+//
+// The native composite check is only equivalent when n > 2, but for
+// smaller n the original loop counts e up without ever reaching n, so
+// it never finishes anyway.
+pub fn optimize(program: &[Instruction]) -> (Vec<Instruction>, Vec<Rewrite>) {
+    let mut out = program.to_vec();
+    let mut rewrites = Vec::new();
+    if has_computed_jump(program) {
+        return (out, rewrites);
+    }
+    let mut pc = 0;
+    while pc + COMPOSITE_LOOP_LEN <= out.len() {
+        let end = pc + COMPOSITE_LOOP_LEN;
+        match composite_loop(&out[pc..end]) {
+            Some((f, n, d, e, g)) if !jumps_into(&out, pc, end) => {
+                out[pc] = Instruction::Composite(f, n);
+                out[pc + 1] = Instruction::Set(d, n);
+                out[pc + 2] = Instruction::Set(e, n);
+                out[pc + 3] = Instruction::Set(g, RI::Imm(0));
+                out[pc + 4] = Instruction::Jnz(RI::Imm(1), RI::Imm((end - (pc + 4)) as i64));
+                rewrites.push(Rewrite {
+                    pc,
+                    len: COMPOSITE_LOOP_LEN,
+                    description: format!("factor search over {d} * {e} == {n} -> composite {f} {n}"),
+                });
+                pc = end;
+            },
+            _ => pc += 1,
+        }
+    }
+    (out, rewrites)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect, VM};
+
+    const PROGRAM: &str = "\
+set b 20
+set c 40
+set h 0
+set f 1
+set x 2
+set y 2
+set z x
+mul z y
+sub z b
+jnz z 2
+set f 0
+sub y -1
+set z y
+sub z b
+jnz z -8
+sub x -1
+set z x
+sub z b
+jnz z -13
+jnz f 2
+sub h -1
+set z b
+sub z c
+jnz z 2
+jnz 1 3
+sub b -5
+jnz 1 -23";
+
+    fn run(program: &[Instruction]) -> Vec<i64> {
+        let mut vm = VM::new(program);
+        vm.run();
+        "bfhxyz".chars().map(|r| vm.get_reg(r)).collect()
+    }
+
+    #[test]
+    fn optimize_test() {
+        let lines: Vec<String> = PROGRAM.lines().map(|l| l.to_string()).collect();
+        let program = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let (optimized, rewrites) = optimize(&program);
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!((rewrites[0].pc, rewrites[0].len), (4, 15));
+        assert_eq!(optimized.len(), program.len());
+        assert_eq!(optimized[4], Instruction::Composite('f', RI::Reg('b')));
+        // 20, 25, 30, 35 and 40 are all composite
+        assert_eq!(run(&optimized), vec![40, 0, 5, 40, 40, 0]);
+        assert_eq!(run(&optimized), run(&program));
+
+        let mut jumped_into = program.clone();
+        jumped_into[3] = Instruction::Jnz(RI::Imm(1), RI::Imm(4));
+        assert!(optimize(&jumped_into).1.is_empty());
+        let mut far = program.clone();
+        far[24] = Instruction::Jnz(RI::Imm(1), RI::Imm(i64::MAX));
+        assert_eq!(optimize(&far).1.len(), 1);
+    }
+}
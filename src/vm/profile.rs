@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::vec::Vec;
+use crate::vm::decompile::disassemble;
+use crate::vm::isa::{Instruction, Opcode};
+use crate::vm::machine::{RunResult, VM};
+use crate::vm::trace::End;
+
+// Execution counts from one run of a program
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Profile {
+    // Indexed by pc
+    pub hits: Vec<u64>,
+    // Times each (from, to) jump back to the same or an earlier pc was taken
+    pub back_edges: BTreeMap<(usize, usize), u64>,
+    pub steps: u64,
+    pub end: End,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HotLoop {
+    // First and last pc of the loop body
+    pub start: usize,
+    pub end: usize,
+    pub iterations: u64,
+    // Instructions executed inside the body, including nested loops
+    pub steps: u64,
+}
+
+// Runs the VM for at most `limit` steps, counting how often each
+// instruction executes.
+pub fn profile(vm: &mut VM, limit: Option<usize>) -> Profile {
+    let mut hits = vec![0; vm.program.len()];
+    let mut back_edges = BTreeMap::new();
+    let mut steps = 0;
+    let end = loop {
+        if vm.is_halted() {
+            break End::Halted;
+        }
+        if limit.is_some_and(|l| steps >= l as u64) {
+            break End::Limit;
+        }
+        let pc = vm.pc;
+        match vm.step() {
+            RunResult::Blocked(r) => break End::Blocked(r),
+            RunResult::Fault { pc, kind } => break End::Fault { pc, kind },
+            _ => {},
+        }
+        hits[pc as usize] += 1;
+        steps += 1;
+        if vm.pc <= pc && vm.pc >= 0 {
+            *back_edges.entry((pc as usize, vm.pc as usize)).or_insert(0) += 1;
+        }
+    };
+    Profile { hits, back_edges, steps, end }
+}
+
+impl Profile {
+    // Executions per opcode, busiest first
+    pub fn by_opcode(&self, program: &[Instruction]) -> Vec<(Opcode, u64)> {
+        let mut counts: Vec<(Opcode, u64)> = Vec::new();
+        for (inst, n) in program.iter().zip(&self.hits) {
+            match counts.iter_mut().find(|(op, _)| *op == inst.opcode()) {
+                Some((_, total)) => *total += n,
+                None => counts.push((inst.opcode(), *n)),
+            }
+        }
+        counts.retain(|(_, n)| *n > 0);
+        counts.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+        counts
+    }
+
+    // Every loop that was taken at least once, by the time spent in it
+    pub fn hot_loops(&self) -> Vec<HotLoop> {
+        let mut loops: Vec<HotLoop> = self.back_edges.iter()
+            .map(|(&(from, to), &iterations)| HotLoop {
+                start: to,
+                end: from,
+                iterations,
+                steps: self.hits[to..=from].iter().sum(),
+            })
+            .collect();
+        loops.sort_by(|a, b| b.steps.cmp(&a.steps).then(a.start.cmp(&b.start)));
+        loops
+    }
+}
+
+// The disassembly with each instruction's hit count and share of the
+// run in front of it; `-` marks instructions that never ran.
+pub fn annotate(program: &[Instruction], profile: &Profile) -> String {
+    let mut out = String::new();
+    for (line, hits) in disassemble(program).lines().zip(&profile.hits) {
+        if *hits == 0 {
+            writeln!(out, "{:>12}        {line}", "-").unwrap();
+        } else {
+            let pct = *hits as f64 * 100.0 / profile.steps as f64;
+            writeln!(out, "{hits:>12} {pct:>5.1}%  {line}").unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    #[test]
+    fn profile_test() {
+        let src = "set a 3\nset b 2\nsub b 1\njnz b -1\nsub a 1\njnz a -4\nmul a a\njnz 1 2\nset h 1";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let p = profile(&mut VM::new(&prog), None);
+        assert_eq!(p.hits, vec![1, 3, 6, 6, 3, 3, 1, 1, 0]);
+        assert_eq!(p.steps, 24);
+        assert_eq!(p.end, End::Halted);
+        assert_eq!(p.by_opcode(&prog)[0], (Opcode::Jnz, 10));
+
+        let loops = p.hot_loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0], HotLoop { start: 1, end: 5, iterations: 2, steps: 21 });
+        assert_eq!(loops[1], HotLoop { start: 2, end: 3, iterations: 3, steps: 12 });
+
+        let listing = annotate(&prog, &p);
+        assert_eq!(listing.lines().nth(2).unwrap(), "           6  25.0%  [2] sub b 1          b -= 1");
+        assert_eq!(listing.lines().last().unwrap(), "           -            set h 1          h = 1");
+
+        let p = profile(&mut VM::new(&prog), Some(5));
+        assert_eq!((p.steps, p.end), (5, End::Limit));
+    }
+}
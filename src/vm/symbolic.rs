@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::vec::Vec;
+use crate::vm::isa::{Instruction, RI};
+
+// Instructions one branch may execute before giving up
+const LIMIT: usize = 100_000;
+// How big a register's formula may get. Squaring in a loop doubles the
+// degree every pass, so this is hit long before LIMIT.
+const MAX_DEGREE: usize = 64;
+const MAX_TERMS: usize = 1000;
+
+// A polynomial over the input registers. Each monomial is its sorted
+// list of variables, repeated for powers; zero coefficients are dropped.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Poly(BTreeMap<Vec<char>, i64>);
+
+impl Poly {
+    pub fn constant(c: i64) -> Self {
+        let mut p = Self::default();
+        if c != 0 {
+            p.0.insert(Vec::new(), c);
+        }
+        p
+    }
+    pub fn var(v: char) -> Self {
+        Self(BTreeMap::from([(vec![v], 1)]))
+    }
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.0.iter().next() {
+            None => Some(0),
+            Some((m, c)) if m.is_empty() && self.0.len() == 1 => Some(*c),
+            _ => None,
+        }
+    }
+    fn too_large(&self) -> bool {
+        self.0.len() > MAX_TERMS || self.0.keys().any(|m| m.len() > MAX_DEGREE)
+    }
+    fn add_term(&mut self, m: Vec<char>, c: i64) -> Option<()> {
+        let sum = self.0.get(&m).unwrap_or(&0).checked_add(c)?;
+        if sum == 0 {
+            self.0.remove(&m);
+        } else {
+            self.0.insert(m, sum);
+        }
+        Some(())
+    }
+    // None on overflow
+    pub fn add(&self, other: &Poly) -> Option<Poly> {
+        let mut p = self.clone();
+        for (m, c) in &other.0 {
+            p.add_term(m.clone(), *c)?;
+        }
+        Some(p)
+    }
+    pub fn sub(&self, other: &Poly) -> Option<Poly> {
+        self.add(&other.mul(&Poly::constant(-1))?)
+    }
+    pub fn mul(&self, other: &Poly) -> Option<Poly> {
+        let mut p = Poly::default();
+        for (m1, c1) in &self.0 {
+            for (m2, c2) in &other.0 {
+                let mut m = m1.clone();
+                m.extend(m2);
+                m.sort();
+                p.add_term(m, c1.checked_mul(*c2)?)?;
+            }
+        }
+        Some(p)
+    }
+    // Replaces the variables that have a value, leaving the others
+    pub fn substitute(&self, values: &BTreeMap<char, i64>) -> Option<Poly> {
+        let mut p = Poly::default();
+        for (m, c) in &self.0 {
+            let mut coeff = *c;
+            let mut rest = Vec::new();
+            for v in m {
+                match values.get(v) {
+                    Some(x) => coeff = coeff.checked_mul(*x)?,
+                    None => rest.push(*v),
+                }
+            }
+            p.add_term(rest, coeff)?;
+        }
+        Some(p)
+    }
+    // (v, value) when the polynomial is zero exactly when v has that value
+    fn solve(&self) -> Option<(char, i64)> {
+        let c0 = *self.0.get(&Vec::new()).unwrap_or(&0);
+        let mut linear = self.0.iter().filter(|(m, _)| !m.is_empty());
+        match (linear.next(), linear.next()) {
+            (Some((m, c1)), None) if m.len() == 1 && c0.checked_rem(*c1) == Some(0) => {
+                Some((m[0], c0.checked_div(*c1)?.checked_neg()?))
+            },
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "0");
+        }
+        // Highest degree first, constant last
+        let mut terms: Vec<(&Vec<char>, &i64)> = self.0.iter().collect();
+        terms.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(b.0)));
+        for (i, (m, c)) in terms.into_iter().enumerate() {
+            let sign = if *c < 0 { "-" } else { "+" };
+            match i {
+                0 if *c < 0 => write!(f, "-")?,
+                0 => {},
+                _ => write!(f, " {sign} ")?,
+            }
+            let vars: Vec<String> = m.iter().map(|v| v.to_string()).collect();
+            match (c.unsigned_abs(), vars.is_empty()) {
+                (c, true) => write!(f, "{c}")?,
+                (1, false) => write!(f, "{}", vars.join(" * "))?,
+                (c, false) => write!(f, "{c} * {}", vars.join(" * "))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Test {
+    Zero,
+    NonZero,
+    Positive,
+    NonPositive,
+}
+
+impl Test {
+    fn holds(self, x: i64) -> bool {
+        match self {
+            Test::Zero => x == 0,
+            Test::NonZero => x != 0,
+            Test::Positive => x > 0,
+            Test::NonPositive => x <= 0,
+        }
+    }
+    // Whether knowing `self` settles `other` for the same value
+    fn implies(self, other: Test) -> Option<bool> {
+        match (self, other) {
+            (a, b) if a == b => Some(true),
+            (Test::Zero, b) => Some(b.holds(0)),
+            (Test::Positive, Test::NonZero) => Some(true),
+            (Test::Positive, _) => Some(false),
+            (Test::NonZero, Test::Zero) | (Test::NonPositive, Test::Positive) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+// What a branch assumed about the inputs at a jump
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Condition {
+    pub value: Poly,
+    pub test: Test,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.test {
+            Test::Zero => "== 0",
+            Test::NonZero => "!= 0",
+            Test::Positive => "> 0",
+            Test::NonPositive => "<= 0",
+        };
+        write!(f, "{} {op}", self.value)
+    }
+}
+
+// One way through the program to the requested pc
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Branch {
+    pub conditions: Vec<Condition>,
+    // Registers that aren't listed are 0
+    pub registers: BTreeMap<char, Poly>,
+    // Inputs pinned down by an equality along the way
+    pub known: BTreeMap<char, i64>,
+    pub steps: usize,
+}
+
+impl Branch {
+    pub fn get(&self, r: char) -> Poly {
+        self.registers.get(&r).cloned().unwrap_or_default()
+    }
+    // Whether the inputs having these values lead down this branch
+    pub fn holds(&self, inputs: &BTreeMap<char, i64>) -> bool {
+        self.conditions.iter().all(|c| {
+            let v = c.value.substitute(inputs).and_then(|p| p.as_constant());
+            v.is_some_and(|v| c.test.holds(v))
+        })
+    }
+    // A register's value for the given inputs
+    pub fn value(&self, r: char, inputs: &BTreeMap<char, i64>) -> Option<i64> {
+        self.get(r).substitute(inputs)?.as_constant()
+    }
+
+    fn set(&mut self, r: char, p: Poly) {
+        if p == Poly::default() {
+            self.registers.remove(&r);
+        } else {
+            self.registers.insert(r, p);
+        }
+    }
+
+    fn resolve(&self, ri: RI) -> Poly {
+        match ri {
+            RI::Reg(r) => self.get(r),
+            RI::Imm(x) => Poly::constant(x),
+        }
+    }
+
+    // Some(answer) when what's already assumed decides the test
+    fn decided(&self, value: &Poly, test: Test) -> Option<bool> {
+        if let Some(x) = value.as_constant() {
+            return Some(test.holds(x));
+        }
+        self.conditions.iter()
+            .filter(|c| c.value == *value)
+            .find_map(|c| c.test.implies(test))
+    }
+
+    // Records an assumption, substituting an input it pins down. None
+    // if that turns out to contradict what was assumed before.
+    fn assume(mut self, value: Poly, test: Test) -> Option<Branch> {
+        if test == Test::Zero {
+            if let Some((v, x)) = value.solve() {
+                self.known.insert(v, x);
+                let known = BTreeMap::from([(v, x)]);
+                for p in self.registers.values_mut() {
+                    *p = p.substitute(&known)?;
+                }
+                self.registers.retain(|_, p| *p != Poly::default());
+                for c in &self.conditions {
+                    if let Some(y) = c.value.substitute(&known)?.as_constant() {
+                        if !c.test.holds(y) {
+                            return None;
+                        }
+                    }
+                }
+            }
+        }
+        self.conditions.push(Condition { value, test });
+        Some(self)
+    }
+}
+
+impl fmt::Display for Branch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conds: Vec<String> = self.conditions.iter().map(|c| c.to_string()).collect();
+        if conds.is_empty() {
+            writeln!(f, "always:")?;
+        } else {
+            writeln!(f, "when {}:", conds.join(" and "))?;
+        }
+        // Inputs that still hold themselves aren't worth listing
+        for (r, p) in self.registers.iter().filter(|(r, p)| **p != Poly::var(**r)) {
+            writeln!(f, "    {r} = {p}")?;
+        }
+        Ok(())
+    }
+}
+
+// Runs the program with the `inputs` registers left as variables and
+// the rest starting at 0, forking at every jump whose condition depends
+// on the inputs, until each branch reaches `at` or halts. Only
+// arithmetic and jumps with constant offsets can be followed; running
+// into anything else, or looping for too long, is an error.
+pub fn symbolic(program: &[Instruction], inputs: &[char], at: usize) -> Result<Vec<Branch>, String> {
+    let start = Branch {
+        conditions: Vec::new(),
+        registers: inputs.iter().map(|r| (*r, Poly::var(*r))).collect(),
+        known: BTreeMap::new(),
+        steps: 0,
+    };
+    let overflow = |pc: usize| format!("overflow at pc {pc}");
+    let bounded = |pc: usize, p: Option<Poly>| match p {
+        None => Err(overflow(pc)),
+        Some(p) if p.too_large() => Err(format!("formula too large at pc {pc}")),
+        Some(p) => Ok(p),
+    };
+    let mut todo = vec![(0i64, start)];
+    let mut done = Vec::new();
+    while let Some((mut pc, mut b)) = todo.pop() {
+        loop {
+            if pc == at as i64 {
+                done.push(b);
+                break;
+            }
+            if pc < 0 || pc >= program.len() as i64 {
+                break;
+            }
+            if b.steps == LIMIT {
+                return Err(format!("a branch ran {LIMIT} steps without reaching pc {at}"));
+            }
+            b.steps += 1;
+            let p = pc as usize;
+            match program[p] {
+                Instruction::Set(x, y) => b.set(x, b.resolve(y)),
+                Instruction::Add(x, y) => b.set(x, bounded(p, b.get(x).add(&b.resolve(y)))?),
+                Instruction::Sub(x, y) => b.set(x, bounded(p, b.get(x).sub(&b.resolve(y)))?),
+                Instruction::Mul(x, y) => b.set(x, bounded(p, b.get(x).mul(&b.resolve(y)))?),
+                Instruction::Mod(x, y) => match (b.get(x).as_constant(), b.resolve(y).as_constant()) {
+                    (Some(n), Some(m)) if m != 0 => b.set(x, Poly::constant(n.checked_rem(m).ok_or_else(|| overflow(p))?)),
+                    _ => return Err(format!("can't take mod of a symbolic value at pc {p}")),
+                },
+                Instruction::Jgz(x, y) | Instruction::Jnz(x, y) => {
+                    let Some(offset) = b.resolve(y).as_constant() else {
+                        return Err(format!("computed jump at pc {p}"));
+                    };
+                    let value = b.resolve(x);
+                    let test = match program[p] {
+                        Instruction::Jgz(_, _) => Test::Positive,
+                        _ => Test::NonZero,
+                    };
+                    let other = if test == Test::Positive { Test::NonPositive } else { Test::Zero };
+                    // A target past either end saturates, and halts like
+                    // any other jump out of the program
+                    match b.decided(&value, test) {
+                        Some(true) => pc = pc.saturating_add(offset).saturating_sub(1),
+                        Some(false) => {},
+                        None => {
+                            if let Some(taken) = b.clone().assume(value.clone(), test) {
+                                todo.push((pc.saturating_add(offset), taken));
+                            }
+                            match b.assume(value, other) {
+                                Some(fallen) => b = fallen,
+                                None => break,
+                            }
+                        },
+                    }
+                },
+                inst => return Err(format!("can't evaluate {inst} symbolically at pc {p}")),
+            }
+            pc = pc.saturating_add(1);
+        }
+    }
+    Ok(done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect};
+
+    #[test]
+    fn symbolic_test() {
+        let lines: Vec<String> = "set b 67\nset c b\njnz a 2\njnz 1 5\nmul b 100\nsub b -100000\n\
+            set c b\nsub c -17000\nset f 1\nset d 2"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let branches = symbolic(&prog, &['a'], 8).unwrap();
+        let text: Vec<String> = branches.iter().map(|b| b.to_string()).collect();
+        assert_eq!(text, vec![
+            "when a == 0:\n    b = 67\n    c = 67\n",
+            "when a != 0:\n    b = 106700\n    c = 123700\n",
+        ]);
+        let one = BTreeMap::from([('a', 1)]);
+        let b = branches.iter().find(|b| b.holds(&one)).unwrap();
+        assert_eq!((b.value('b', &one), b.value('c', &one)), (Some(106700), Some(123700)));
+        assert_eq!(branches[0].known, BTreeMap::from([('a', 0)]));
+
+        let lines: Vec<String> = "set b a\nmul b a\nsub b x\nmul x 3\nsub x -2\njnz b 2\nset c 1"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let branches = symbolic(&prog, &['a', 'x'], 7).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].get('b').to_string(), "a * a - x");
+        assert_eq!(branches[0].get('x').to_string(), "3 * x + 2");
+        assert_eq!(branches[0].conditions[0].to_string(), "a * a - x == 0");
+        assert_eq!(branches[1].get('c'), Poly::default());
+
+        // i64::MIN by -1 can't be done, so the branch just isn't solved
+        let lines: Vec<String> = "set b a\nmul b -1\nsub b 1\nsub b 9223372036854775807\njnz b 2\nset c 1"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let branches = symbolic(&prog, &['a'], 6).unwrap();
+        assert!(branches[0].known.is_empty());
+        let lines: Vec<String> = "set b -9223372036854775808\nmod b -1".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        assert_eq!(symbolic(&prog, &['a'], 2).err().unwrap(), "overflow at pc 1");
+
+        let run = |src: &str, at| {
+            let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+            symbolic(&parse_program(&lines, Dialect::Coprocessor).unwrap(), &['a'], at)
+        };
+        assert_eq!(run("set b 1\njnz 1 9223372036854775807", 5), Ok(vec![]));
+        assert_eq!(run("jnz a 9223372036854775807\nset b 1", 2).unwrap().len(), 1);
+        assert_eq!(run("set b a\nmul b b\njnz 1 -1", 5).err().unwrap(), "formula too large at pc 1");
+    }
+}
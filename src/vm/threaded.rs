@@ -0,0 +1,312 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TryRecvError, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::vec::Vec;
+use crate::vm::isa::Instruction;
+use crate::vm::machine::{Arithmetic, FaultKind, RunResult, VM};
+use crate::vm::network::{Blocked, ChannelStats, Faulted, Outcome, ProgramState, Report, Topology, Wait};
+
+const POLL: Duration = Duration::from_millis(5);
+
+// Values carry the index of the link they were sent on
+type Message = (usize, i64);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    Running,
+    Waiting(char),
+    // Trying to send on a full channel
+    Stalled(usize),
+    Faulted(FaultKind),
+    Halted,
+}
+
+// Bookkeeping for the quiescence detector. Each thread updates its own
+// state under the lock, and whichever one makes the last transition out
+// of Running notices that nothing can move any more.
+struct Shared {
+    states: Vec<State>,
+    // Messages sent to each program and not yet taken off its channel.
+    // Bumped before a send is attempted, so it may briefly over-count,
+    // which can only delay detection, never fake it.
+    queued: Vec<usize>,
+    sent: Vec<usize>,
+    received: Vec<usize>,
+    links: Vec<(usize, usize)>,
+    capacity: usize,
+    done: bool,
+}
+
+impl Shared {
+    fn quiescent(&self) -> bool {
+        self.states.iter().enumerate().all(|(id, s)| match *s {
+            State::Running => false,
+            State::Waiting(_) => self.queued[id] == 0,
+            // A halted or faulted receiver will drop its end, so the
+            // sender only looks full until it retries
+            State::Stalled(link) => {
+                let to = self.links[link].1;
+                self.queued[to] >= self.capacity
+                    && matches!(self.states[to], State::Waiting(_) | State::Stalled(_))
+            },
+            State::Faulted(_) | State::Halted => true,
+        })
+    }
+    fn set_state(&mut self, id: usize, state: State) {
+        self.states[id] = state;
+        if self.quiescent() {
+            self.done = true;
+        }
+    }
+}
+
+struct Worker<'a, 'b> {
+    id: usize,
+    vm: VM<'a>,
+    rx: Receiver<Message>,
+    // (link, destination, sender) for each outgoing link
+    outgoing: Vec<(usize, usize, SyncSender<Message>)>,
+    shared: &'b Mutex<Shared>,
+}
+
+impl Worker<'_, '_> {
+    // Returns false once the detector has called the run off.
+    fn send(&self, link: usize, to: usize, tx: &SyncSender<Message>, v: i64) -> bool {
+        loop {
+            {
+                let mut sh = self.shared.lock().unwrap();
+                if sh.done {
+                    return false;
+                }
+                sh.queued[to] += 1;
+                sh.states[self.id] = State::Running;
+            }
+            let result = tx.try_send((link, v));
+            let mut sh = self.shared.lock().unwrap();
+            match result {
+                Ok(()) => {
+                    sh.sent[link] += 1;
+                    return true;
+                },
+                // The receiver halted; the value is lost
+                Err(TrySendError::Disconnected(_)) => {
+                    sh.queued[to] -= 1;
+                    sh.sent[link] += 1;
+                    return true;
+                },
+                Err(TrySendError::Full(_)) => {
+                    sh.queued[to] -= 1;
+                    sh.set_state(self.id, State::Stalled(link));
+                },
+            }
+            drop(sh);
+            thread::sleep(POLL);
+        }
+    }
+
+    fn take(&self, (link, v): Message) -> i64 {
+        let mut sh = self.shared.lock().unwrap();
+        sh.queued[self.id] -= 1;
+        sh.received[link] += 1;
+        sh.states[self.id] = State::Running;
+        v
+    }
+
+    fn receive(&self, reg: char) -> Option<i64> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(msg) => return Some(self.take(msg)),
+                Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => {},
+            }
+            {
+                let mut sh = self.shared.lock().unwrap();
+                if sh.done {
+                    return None;
+                }
+                if sh.queued[self.id] > 0 {
+                    // A sender has counted a message that hasn't landed yet
+                    drop(sh);
+                    thread::yield_now();
+                    continue;
+                }
+                sh.set_state(self.id, State::Waiting(reg));
+                if sh.done {
+                    return None;
+                }
+            }
+            match self.rx.recv_timeout(POLL) {
+                Ok(msg) => return Some(self.take(msg)),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {},
+            }
+        }
+    }
+
+    fn run(mut self) -> ProgramState {
+        loop {
+            match self.vm.run() {
+                RunResult::Snd(v) => {
+                    for (link, to, tx) in &self.outgoing {
+                        if !self.send(*link, *to, tx, v) {
+                            return self.state();
+                        }
+                    }
+                },
+                RunResult::Blocked(reg) => match self.receive(reg) {
+                    Some(v) => self.vm.inbox.push_back(v),
+                    None => return self.state(),
+                },
+                RunResult::Fault { kind, .. } => {
+                    self.shared.lock().unwrap().set_state(self.id, State::Faulted(kind));
+                    return self.state();
+                },
+                _ => {
+                    self.shared.lock().unwrap().set_state(self.id, State::Halted);
+                    return self.state();
+                },
+            }
+        }
+    }
+
+    fn state(&self) -> ProgramState {
+        // A snd that was never delivered still counts as sent by the VM
+        let sent = match self.shared.lock().unwrap().states[self.id] {
+            State::Stalled(_) => self.vm.sent - 1,
+            _ => self.vm.sent,
+        };
+        ProgramState { pc: self.vm.pc, halted: self.vm.is_halted(), sent }
+    }
+}
+
+// Runs the same network as Network::run, but with every program on its
+// own thread and each program's incoming links merged into one bounded
+// channel of `capacity` values. Unlike the cooperative scheduler this
+// can also deadlock on full channels, which is reported as such.
+pub fn run_threaded(
+    program: &[Instruction],
+    n: usize,
+    topology: &Topology,
+    capacity: usize,
+    arithmetic: Arithmetic,
+) -> Result<Report, String> {
+    let links = topology.links(n)?;
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..n).map(|_| mpsc::sync_channel(capacity)).unzip();
+    let shared = Mutex::new(Shared {
+        states: vec![State::Running; n],
+        queued: vec![0; n],
+        sent: vec![0; links.len()],
+        received: vec![0; links.len()],
+        links: links.clone(),
+        capacity,
+        done: false,
+    });
+    let programs: Vec<ProgramState> = thread::scope(|scope| {
+        let handles: Vec<_> = rxs.into_iter()
+            .enumerate()
+            .map(|(id, rx)| {
+                let outgoing = links.iter()
+                    .enumerate()
+                    .filter(|(_, (from, _))| *from == id)
+                    .map(|(link, (_, to))| (link, *to, txs[*to].clone()))
+                    .collect();
+                let shared = &shared;
+                // The VM is built on its own thread since a trap need not be Send
+                scope.spawn(move || {
+                    let mut vm = VM::new(program);
+                    vm.set_reg('p', id as i64);
+                    vm.arithmetic = arithmetic;
+                    Worker { id, vm, rx, outgoing, shared }.run()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let sh = shared.into_inner().unwrap();
+    let faulted: Vec<Faulted> = sh.states.iter()
+        .enumerate()
+        .filter_map(|(id, s)| match *s {
+            State::Faulted(kind) => Some(Faulted { program: id, pc: programs[id].pc, kind }),
+            _ => None,
+        })
+        .collect();
+    let blocked: Vec<Blocked> = sh.states.iter()
+        .enumerate()
+        .filter_map(|(id, s)| {
+            let wait = match *s {
+                State::Waiting(reg) => {
+                    let incoming = (0..links.len()).filter(|l| links[*l].1 == id).collect();
+                    Wait::Recv(reg, incoming)
+                },
+                State::Stalled(link) => Wait::Send(link),
+                _ => return None,
+            };
+            // A stalled snd has already moved the pc past itself
+            let pc = programs[id].pc - matches!(wait, Wait::Send(_)) as i64;
+            Some(Blocked { program: id, pc, wait })
+        })
+        .collect();
+    let outcome = if !faulted.is_empty() {
+        Outcome::Fault(faulted)
+    } else if blocked.is_empty() {
+        Outcome::AllHalted
+    } else {
+        Outcome::Deadlock(blocked)
+    };
+    let channels = links.iter()
+        .enumerate()
+        .map(|(l, (from, to))| ChannelStats { from: *from, to: *to, sent: sh.sent[l], received: sh.received[l] })
+        .collect();
+    Ok(Report { outcome, programs, channels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{parse_program, Dialect, Network};
+
+    #[test]
+    fn threaded_test() {
+        let src = "snd p\nset c 2\nrcv a\nadd a p\nsnd a\nadd c -1\njgz c -4\nrcv b\nrcv b";
+        let lines: Vec<String> = src.lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        for topology in [Topology::Ring, Topology::Broadcast] {
+            let mut net = Network::new(&prog, 3, &topology).unwrap();
+            net.run();
+            let threaded = run_threaded(&prog, 3, &topology, 16, Arithmetic::Checked).unwrap();
+            assert_eq!(threaded.programs, net.report().programs);
+            if topology == Topology::Ring {
+                assert_eq!(threaded, net.report());
+            }
+        }
+
+        // Both programs send three values before receiving any, so a
+        // capacity of two leaves them stuck on full channels.
+        let lines: Vec<String> = "snd 1\nsnd 2\nsnd p\nrcv a".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let report = run_threaded(&prog, 2, &Topology::Ring, 2, Arithmetic::Checked).unwrap();
+        let Outcome::Deadlock(blocked) = &report.outcome else { panic!() };
+        assert_eq!(blocked[0].wait, Wait::Send(0));
+        assert_eq!(blocked[1].wait, Wait::Send(1));
+        assert_eq!(report.programs[1].sent, 2);
+        assert!(report.to_string().starts_with("deadlock: program 0 at pc 2 (snd) waiting on full 0->1;"));
+
+        let lines: Vec<String> = "snd p\nrcv a\nmod p a".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let report = run_threaded(&prog, 2, &Topology::Ring, 2, Arithmetic::Wrapping).unwrap();
+        let expected = Faulted { program: 1, pc: 2, kind: FaultKind::DivideByZero };
+        assert_eq!(report.outcome, Outcome::Fault(vec![expected]));
+
+        // Program 1 jumps straight out and halts while 0 fills the
+        // channel to it, which is not a deadlock: the sends after the
+        // halt are just lost.
+        let lines: Vec<String> = "jgz p 5\nsnd 1\nsnd 2\nsnd 3\nset a 9".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let mut net = Network::new(&prog, 2, &Topology::Ring).unwrap();
+        assert_eq!(net.run(), Outcome::AllHalted);
+        for _ in 0..20 {
+            let report = run_threaded(&prog, 2, &Topology::Ring, 1, Arithmetic::Checked).unwrap();
+            assert_eq!(report, net.report());
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::fmt;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::vm::isa::Instruction;
+use crate::vm::machine::{FaultKind, Registers, RunResult, VM};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Io {
+    Snd(i64),
+    Rcv(i64),
+}
+
+// One executed instruction
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event {
+    pub pc: i64,
+    pub inst: String,
+    // Registers whose value changed, in name order
+    pub writes: Vec<(char, i64)>,
+    pub io: Option<Io>,
+}
+
+// Why recording stopped
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum End {
+    Halted,
+    Blocked(char),
+    Fault { pc: i64, kind: FaultKind },
+    Limit,
+}
+
+// Stored one event per line, as `pc: instruction` followed by
+// ` => ` and its effects when it had any:
+//
+//   4: mul b 100 => b=6700
+//   9: snd a => snd=4
+//   10: rcv c => c=7 rcv=7
+//   # end halted
+//
+// or `# end blocked c`, `# end fault at 12: overflow` or `# end limit`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trace {
+    pub events: Vec<Event>,
+    pub end: End,
+}
+
+fn writes(before: &Registers, after: &Registers) -> Vec<(char, i64)> {
+    ('a'..='z')
+        .filter(|r| before.get(*r) != after.get(*r))
+        .map(|r| (r, after.get(r)))
+        .collect()
+}
+
+// Executes one instruction, describing what it did, or says why it
+// couldn't.
+fn step(vm: &mut VM) -> Result<Event, End> {
+    if vm.is_halted() {
+        return Err(End::Halted);
+    }
+    let pc = vm.pc;
+    let inst = vm.program[pc as usize];
+    let before = vm.registers.clone();
+    let io = match vm.step() {
+        RunResult::Blocked(r) => return Err(End::Blocked(r)),
+        RunResult::Fault { pc, kind } => return Err(End::Fault { pc, kind }),
+        RunResult::Snd(v) => Some(Io::Snd(v)),
+        _ => match inst {
+            Instruction::Rcv(r) => Some(Io::Rcv(vm.get_reg(r))),
+            _ => None,
+        },
+    };
+    Ok(Event { pc, inst: inst.to_string(), writes: writes(&before, &vm.registers), io })
+}
+
+// Runs the VM for at most `limit` steps, recording each one.
+pub fn record(vm: &mut VM, limit: Option<usize>) -> Trace {
+    let mut events = Vec::new();
+    loop {
+        if limit.is_some_and(|l| events.len() >= l) {
+            return Trace { events, end: End::Limit };
+        }
+        match step(vm) {
+            Ok(e) => events.push(e),
+            Err(end) => return Trace { events, end },
+        }
+    }
+}
+
+// What a run did at one step: an instruction, or why it stopped
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Entry {
+    Event(Event),
+    End(End),
+}
+
+impl Trace {
+    fn entry(&self, i: usize) -> Entry {
+        match self.events.get(i) {
+            Some(e) => Entry::Event(e.clone()),
+            None => Entry::End(self.end.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Divergence {
+    // 1-based step number, which is also the line number in the file
+    pub step: usize,
+    pub expected: Entry,
+    pub actual: Entry,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "traces diverge at step {}", self.step)?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        write!(f, "  actual:   {}", self.actual)
+    }
+}
+
+// The first step where two traces differ, counting how each one ended
+// as a step of its own. Nothing is known past a limit, so a trace that
+// was cut short matches anything from there on.
+pub fn diff(expected: &Trace, actual: &Trace) -> Option<Divergence> {
+    let n = expected.events.len().max(actual.events.len());
+    (0..=n)
+        .map(|i| (i, expected.entry(i), actual.entry(i)))
+        .take_while(|(_, e, a)| *e != Entry::End(End::Limit) && *a != Entry::End(End::Limit))
+        .find(|(_, e, a)| e != a)
+        .map(|(i, expected, actual)| Divergence { step: i + 1, expected, actual })
+}
+
+// Re-runs a recorded trace on a fresh VM, feeding it the values the
+// original run received, and checks every step matches, as well as
+// how the run stopped.
+pub fn replay(trace: &Trace, vm: &mut VM) -> Result<(), Box<Divergence>> {
+    for i in 0..=trace.events.len() {
+        let expected = trace.entry(i);
+        match &expected {
+            Entry::Event(Event { io: Some(Io::Rcv(v)), .. }) => vm.inbox.push_back(*v),
+            Entry::End(End::Limit) => return Ok(()),
+            _ => {},
+        }
+        let actual = match step(vm) {
+            Ok(e) => Entry::Event(e),
+            Err(end) => Entry::End(end),
+        };
+        if actual != expected {
+            return Err(Box::new(Divergence { step: i + 1, expected, actual }));
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pc, self.inst)?;
+        let mut effects: Vec<String> = self.writes.iter().map(|(r, v)| format!("{r}={v}")).collect();
+        match self.io {
+            Some(Io::Snd(v)) => effects.push(format!("snd={v}")),
+            Some(Io::Rcv(v)) => effects.push(format!("rcv={v}")),
+            None => {},
+        }
+        if !effects.is_empty() {
+            write!(f, " => {}", effects.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for End {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            End::Halted => write!(f, "halted"),
+            End::Blocked(r) => write!(f, "blocked {r}"),
+            End::Fault { pc, kind } => write!(f, "fault at {pc}: {kind}"),
+            End::Limit => write!(f, "limit"),
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Event(e) => write!(f, "{e}"),
+            Entry::End(end) => write!(f, "# end {end}"),
+        }
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for e in &self.events {
+            writeln!(f, "{e}")?;
+        }
+        writeln!(f, "# end {}", self.end)
+    }
+}
+
+impl FromStr for Event {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (head, effects) = s.split_once(" => ").unwrap_or((s, ""));
+        let (pc, inst) = head.split_once(": ").ok_or("expected `pc: instruction`")?;
+        let pc = pc.parse::<i64>().map_err(|_| format!("bad pc: {pc}"))?;
+        let mut writes = Vec::new();
+        let mut io = None;
+        for effect in effects.split_whitespace() {
+            let (k, v) = effect.split_once('=').ok_or_else(|| format!("bad effect: {effect}"))?;
+            let v = v.parse::<i64>().map_err(|_| format!("bad value: {effect}"))?;
+            match k {
+                "snd" => io = Some(Io::Snd(v)),
+                "rcv" => io = Some(Io::Rcv(v)),
+                _ => {
+                    let mut chars = k.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(r), None) => writes.push((r, v)),
+                        _ => return Err(format!("bad register: {k}")),
+                    }
+                },
+            }
+        }
+        Ok(Event { pc, inst: inst.to_string(), writes, io })
+    }
+}
+
+fn parse_end(s: &str) -> Option<End> {
+    if let Some(r) = s.strip_prefix("blocked ") {
+        let mut chars = r.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(r), None) => Some(End::Blocked(r)),
+            _ => None,
+        };
+    }
+    let (pc, kind) = s.strip_prefix("fault at ")?.split_once(": ")?;
+    let kind = match kind {
+        "overflow" => FaultKind::Overflow,
+        "division by zero" => FaultKind::DivideByZero,
+        _ => return None,
+    };
+    Some(End::Fault { pc: pc.parse().ok()?, kind })
+}
+
+impl FromStr for Trace {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        let mut end = None;
+        for (idx, line) in s.lines().enumerate() {
+            if let Some(e) = line.strip_prefix("# end ") {
+                end = Some(match e {
+                    "halted" => End::Halted,
+                    "limit" => End::Limit,
+                    _ => match parse_end(e) {
+                        Some(end) => end,
+                        None => return Err(format!("line {}: bad end marker", idx + 1)),
+                    },
+                });
+            } else if !line.is_empty() {
+                events.push(line.parse::<Event>().map_err(|e| format!("line {}: {e}", idx + 1))?);
+            }
+        }
+        let end = end.ok_or("missing `# end` line")?;
+        Ok(Trace { events, end })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{optimize, parse_program, Dialect};
+
+    #[test]
+    fn trace_test() {
+        let lines: Vec<String> = "set a 2\nsnd a\nrcv b\nmul b a\njgz b -3"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let mut vm = VM::new(&prog);
+        vm.inbox.push_back(5);
+        let trace = record(&mut vm, None);
+        let text = trace.to_string();
+        assert_eq!(text.lines().take(4).collect::<Vec<_>>(), vec![
+            "0: set a 2 => a=2",
+            "1: snd a => snd=2",
+            "2: rcv b => b=5 rcv=5",
+            "3: mul b a => b=10",
+        ]);
+        assert_eq!(trace.end, End::Blocked('b'));
+        assert_eq!(text.parse::<Trace>().unwrap(), trace);
+
+        // Replay supplies the received values itself
+        assert_eq!(replay(&trace, &mut VM::new(&prog)), Ok(()));
+        let lines: Vec<String> = "set a 2\nsnd a\nrcv b\nadd b a\njgz b -3"
+            .lines().map(|l| l.to_string()).collect();
+        let changed = parse_program(&lines, Dialect::Duet).unwrap();
+        let err = replay(&trace, &mut VM::new(&changed)).unwrap_err();
+        assert_eq!(err.step, 4);
+
+        let lines: Vec<String> = "set b 7\nset f 1\nset d 2\nset e 2\nset g d\nmul g e\nsub g b\njnz g 2\n\
+            set f 0\nsub e -1\nset g e\nsub g b\njnz g -8\nsub d -1\nset g d\nsub g b\njnz g -13"
+            .lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Coprocessor).unwrap();
+        let (fast, _) = optimize(&prog);
+        let slow = record(&mut VM::new(&prog), Some(50));
+        let quick = record(&mut VM::new(&fast), None);
+        let div = diff(&slow, &quick).unwrap();
+        assert_eq!(div.step, 3);
+        assert_eq!(div.to_string(), "\
+traces diverge at step 3
+  expected: 2: set d 2 => d=2
+  actual:   2: composite f b");
+        assert!(diff(&slow, &slow).is_none());
+
+        let lines: Vec<String> = "set a 5\nmod a b".lines().map(|l| l.to_string()).collect();
+        let prog = parse_program(&lines, Dialect::Duet).unwrap();
+        let trace = record(&mut VM::new(&prog), None);
+        assert_eq!(trace.to_string(), "0: set a 5 => a=5\n# end fault at 1: division by zero\n");
+        assert_eq!(trace.to_string().parse::<Trace>().unwrap(), trace);
+
+        // Same steps, but one run halts where the other faults
+        let halted = Trace { end: End::Halted, ..trace.clone() };
+        assert_eq!(diff(&halted, &trace).unwrap().to_string(), "\
+traces diverge at step 2
+  expected: # end halted
+  actual:   # end fault at 1: division by zero");
+        let err = replay(&halted, &mut VM::new(&prog)).unwrap_err();
+        assert_eq!((err.step, err.actual), (2, Entry::End(End::Fault { pc: 1, kind: FaultKind::DivideByZero })));
+        assert_eq!(replay(&trace, &mut VM::new(&prog)), Ok(()));
+    }
+}